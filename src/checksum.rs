@@ -0,0 +1,127 @@
+//! Check-digit calculations used by the symbologies.
+//!
+//! These are available on their own, so that data can be validated (or completed) without
+//! constructing a barcode. Each function takes the values that the symbology would check, which
+//! are digits for `mod10` and positions in the symbology's character table for the others.
+//!
+//! For example:
+//!
+//! ```rust
+//! use barcoders::checksum;
+//!
+//! // The check digit of the UPC-A 03600029145.
+//! assert_eq!(checksum::mod10(&[0, 3, 6, 0, 0, 0, 2, 9, 1, 4, 5]), 2);
+//! ```
+
+/// Calculates the GS1 modulo-10 check digit of the given digits, as used by EAN, UPC and ITF.
+/// Digits are weighted 3 and 1 alternately, starting from the rightmost digit.
+pub fn mod10(digits: &[u8]) -> u8 {
+    // Sums are kept wider than the digits themselves, as long inputs would overflow a u8.
+    let sum: u32 = digits.iter()
+                         .rev()
+                         .enumerate()
+                         .map(|(i, &d)| u32::from(d) * if i % 2 == 0 { 3 } else { 1 })
+                         .sum();
+
+    ((10 - (sum % 10)) % 10) as u8
+}
+
+/// Calculates the Luhn modulo-10 check digit of the given digits, as used by MSI. Every other
+/// digit is doubled (summing the digits of the product), starting from the rightmost digit.
+pub fn luhn(digits: &[u8]) -> u8 {
+    let sum: u32 = digits.iter()
+                         .rev()
+                         .enumerate()
+                         .map(|(i, &d)| if i % 2 == 0 { (u32::from(d) * 2) % 10 + (u32::from(d) * 2) / 10 } else { u32::from(d) })
+                         .sum();
+
+    ((10 - (sum % 10)) % 10) as u8
+}
+
+/// Calculates the weighted modulo-11 check value of the given Code11 values. Weights run from 1
+/// at the rightmost value up to `max_weight`, then start again from 1. Code11 uses a maximum
+/// weight of 10 for its C checksum and 9 for its K checksum.
+pub fn mod11(values: &[usize], max_weight: usize) -> usize {
+    weighted_sum(values, max_weight) % 11
+}
+
+/// Calculates the modulo-43 check value of the given Code39 values, which is their sum.
+pub fn mod43(symbols: &[usize]) -> usize {
+    symbols.iter().sum::<usize>() % 43
+}
+
+/// Calculates the weighted modulo-47 check value of the given Code93 values. Weights run from 1
+/// at the rightmost value up to `max_weight`, then start again from 1. Code93 uses a maximum
+/// weight of 20 for its C checksum and 15 for its K checksum.
+pub fn mod47(values: &[usize], max_weight: usize) -> usize {
+    weighted_sum(values, max_weight) % 47
+}
+
+/// Calculates the weighted modulo-103 check value of the given Code128 values, starting with
+/// the START symbol. The START symbol and the first value after it both have a weight of 1,
+/// with each later value weighted by its position.
+pub fn mod103(values: &[usize]) -> usize {
+    values.iter()
+          .enumerate()
+          .map(|(i, &v)| v * i.max(1))
+          .sum::<usize>() % 103
+}
+
+// Sums the values with weights cycling from 1 to max_weight, starting from the rightmost value.
+fn weighted_sum(values: &[usize], max_weight: usize) -> usize {
+    values.iter()
+          .rev()
+          .enumerate()
+          .map(|(i, &v)| v * ((i % max_weight) + 1))
+          .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use checksum::*;
+
+    #[test]
+    fn mod10_vectors() {
+        // UPC-A 036000291452, EAN-13 4006381333931 and EAN-8 96385074.
+        assert_eq!(mod10(&[0, 3, 6, 0, 0, 0, 2, 9, 1, 4, 5]), 2);
+        assert_eq!(mod10(&[4, 0, 0, 6, 3, 8, 1, 3, 3, 3, 9, 3]), 1);
+        assert_eq!(mod10(&[9, 6, 3, 8, 5, 0, 7]), 4);
+        assert_eq!(mod10(&[]), 0);
+    }
+
+    #[test]
+    fn luhn_vectors() {
+        // MSI 1234567 and the Luhn example 7992739871.
+        assert_eq!(luhn(&[1, 2, 3, 4, 5, 6, 7]), 4);
+        assert_eq!(luhn(&[7, 9, 9, 2, 7, 3, 9, 8, 7, 1]), 3);
+        assert_eq!(luhn(&[]), 0);
+    }
+
+    #[test]
+    fn mod11_vectors() {
+        // Code11 123-45 has the check characters 5 and 2.
+        assert_eq!(mod11(&[1, 2, 3, 10, 4, 5], 10), 5);
+        assert_eq!(mod11(&[1, 2, 3, 10, 4, 5, 5], 9), 2);
+    }
+
+    #[test]
+    fn mod43_vectors() {
+        // Code39 CODE39 has the check character W.
+        assert_eq!(mod43(&[12, 24, 13, 14, 3, 9]), 32);
+        assert_eq!(mod43(&[42, 1]), 0);
+    }
+
+    #[test]
+    fn mod47_vectors() {
+        // Code93 TEST93 has the check characters + and 6.
+        assert_eq!(mod47(&[29, 14, 28, 29, 9, 3], 20), 41);
+        assert_eq!(mod47(&[29, 14, 28, 29, 9, 3, 41], 15), 6);
+    }
+
+    #[test]
+    fn mod103_vectors() {
+        // Code128 PJJ123C in character-set B.
+        assert_eq!(mod103(&[104, 48, 42, 42, 17, 18, 19, 35]), 55);
+        assert_eq!(mod103(&[104]), 1);
+    }
+}
@@ -12,6 +12,22 @@ pub enum Error {
   Length,
   /// An error during barcode generation.
   Generate,
+  /// An unknown symbology was requested.
+  Symbology,
+  /// A provided check digit does not match the data.
+  Checksum,
+}
+
+/// Non-fatal problems that may affect the scannability of an encoded barcode.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Warning {
+    /// The encoding exceeds the practical capacity of the symbology.
+    Capacity {
+        /// The number of modules in the encoding.
+        modules: usize,
+        /// The recommended maximum number of modules.
+        recommended: usize,
+    },
 }
 
 /// Alias-type for Result<T, barcoders::error::Error>.
@@ -19,20 +35,20 @@ pub type Result<T> = ::std::result::Result<T, Error>;
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str(self.description())
-    }
-}
-
-impl StdError for Error {
-    fn description(&self) -> &str {
-        match *self {
+        let description = match *self {
             Error::Character => "Barcode data is invalid",
             Error::Length => "Barcode data length is invalid",
             Error::Generate => "Could not generate barcode data",
-        }
+            Error::Symbology => "Barcode symbology is unknown",
+            Error::Checksum => "Barcode checksum is invalid",
+        };
+
+        f.write_str(description)
     }
+}
 
-    fn cause(&self) -> Option<&StdError> {
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         None
     }
 }
@@ -6,7 +6,8 @@
 //! or running the test suite.
 
 use std::iter::repeat;
-use error::Result;
+use error::{Error, Result};
+use sym::Barcode;
 
 /// The ASCII barcode generator type.
 #[derive(Copy, Clone, Debug)]
@@ -21,6 +22,15 @@ pub struct ASCII {
 /// Maps binary digits to ASCII representation (0=' ', 1='#')
 const CHARS: [char; 2] = [' ', '#'];
 
+/// Maps pairs of binary digits (upper, lower) to Unicode half-blocks.
+const HALF_BLOCKS: [[char; 2]; 2] = [[' ', '\u{2584}'], ['\u{2580}', '\u{2588}']];
+
+impl Default for ASCII {
+    fn default() -> ASCII {
+        ASCII::new()
+    }
+}
+
 impl ASCII {
     /// Returns a new ASCII with default values.
     pub fn new() -> ASCII {
@@ -30,27 +40,81 @@ impl ASCII {
         }
     }
 
-    fn generate_row(&self, barcode: &[u8]) -> String {
-        barcode.iter()
-               .flat_map(|&d| repeat(CHARS[d as usize]).take(self.xdim))
-               .collect()
+    // Modules other than 0 or 1 cannot be represented, and are reported as a generation error.
+    fn generate_row(&self, barcode: &[u8]) -> Result<String> {
+        let mut row = String::new();
+
+        for &d in barcode {
+            let c = CHARS.get(d as usize).ok_or(Error::Generate)?;
+            row.extend(repeat(*c).take(self.xdim));
+        }
+
+        Ok(row)
     }
 
     /// Generates the given barcode. Returns a `Result<String, Error>` indicating success.
     pub fn generate<T: AsRef<[u8]>>(&self, barcode: T) -> Result<String> {
         let mut output = String::new();
-        let row = self.generate_row(barcode.as_ref());
+        let row = self.generate_row(barcode.as_ref())?;
 
         for (i, _l) in (0..self.height).enumerate() {
             output.push_str(&row[..]);
 
             if i < self.height - 1 {
-                output.push_str("\n");
+                output.push('\n');
             }
         }
 
         Ok(output)
     }
+
+    /// Generates the given matrix of binary digits (as produced by stacked and 2D symbologies),
+    /// one line per row. Each module is ```self.xdim``` characters wide, while the height is
+    /// ignored. Returns a `Result<String, Error>` indicating success.
+    pub fn generate_matrix(&self, matrix: &[Vec<u8>]) -> Result<String> {
+        let rows = matrix.iter()
+                         .map(|r| self.generate_row(r))
+                         .collect::<Result<Vec<String>>>()?;
+
+        Ok(rows.join("\n"))
+    }
+
+    /// Generates the given matrix of binary digits as with `generate_matrix`, but using
+    /// Unicode half-blocks to draw two rows per line, which keeps modules roughly square in
+    /// most terminals. All rows must be the same length.
+    /// Returns a `Result<String, Error>` indicating success.
+    pub fn generate_matrix_compact(&self, matrix: &[Vec<u8>]) -> Result<String> {
+        let width = matrix.first().map_or(0, |r| r.len());
+
+        if matrix.iter().any(|r| r.len() != width) {
+            return Err(Error::Generate);
+        }
+
+        let blank = vec![0; width];
+        let lines = matrix.chunks(2)
+            .map(|pair| {
+                let lower = pair.get(1).unwrap_or(&blank);
+                let mut line = String::new();
+
+                for (&u, &l) in pair[0].iter().zip(lower.iter()) {
+                    let c = HALF_BLOCKS.get(u as usize)
+                                       .and_then(|h| h.get(l as usize))
+                                       .ok_or(Error::Generate)?;
+                    line.extend(repeat(*c).take(self.xdim));
+                }
+
+                Ok(line)
+            })
+            .collect::<Result<Vec<String>>>()?;
+
+        Ok(lines.join("\n"))
+    }
+
+    /// Generates the given barcode directly from the symbology. Guards and human-readable
+    /// text are not drawn in ASCII. Returns a `Result<String, Error>` indicating success.
+    pub fn generate_from<B: Barcode + ?Sized>(&self, barcode: &B) -> Result<String> {
+        self.generate(barcode.encode())
+    }
 }
 
 #[cfg(test)]
@@ -104,6 +168,26 @@ mod tests {
 ".trim());
     }
 
+    #[test]
+    fn ean_13_as_ascii_from_barcode() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let ascii = ASCII::new();
+
+        assert_eq!(ascii.generate_from(&ean13).unwrap(), ascii.generate(&ean13.encode()[..]).unwrap());
+    }
+
+    #[test]
+    fn matrix_as_ascii() {
+        let matrix = vec![vec![1, 0, 1], vec![0, 1, 1], vec![1, 1, 0]];
+        let ascii = ASCII{height: 3, xdim: 2};
+
+        assert_eq!(ascii.generate_matrix(&matrix).unwrap(), "##  ##\n  ####\n####  ");
+        assert_eq!(ascii.generate_matrix_compact(&matrix).unwrap(), "▀▀▄▄██\n▀▀▀▀  ");
+        assert_eq!(ascii.generate_matrix(&[vec![2]]).err().unwrap(), Error::Generate);
+        assert_eq!(ascii.generate_matrix_compact(&[vec![1], vec![1, 0]]).err().unwrap(), Error::Generate);
+        assert_eq!(ascii.generate_matrix(&[]).unwrap(), "");
+    }
+
     #[test]
     fn ean_8_as_ascii() {
         let ean8 = EAN8::new("1234567").unwrap();
@@ -380,4 +464,11 @@ mod tests {
 # ##  # ## # ## #  # ## # ## # ## # # #  ## # # ##  #
 ".trim());
     }
+
+    #[test]
+    fn invalid_modules_as_ascii() {
+        let ascii = ASCII::new();
+
+        assert_eq!(ascii.generate(&[1, 0, 2][..]).err(), Some(::error::Error::Generate));
+    }
 }
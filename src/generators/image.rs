@@ -13,18 +13,28 @@
 //!                      xdim: 1,
 //!                      rotation: Rotation::Zero,
 //!                      foreground: Color::new([0, 0, 0, 255]),
-//!                      background: Color::new([255, 255, 255, 255])};
+//!                      background: Color::new([255, 255, 255, 255]),
+//!                      bar_width_reduction: 0.0,
+//!                      invert: false,
+//!                      left_quiet_zone: 0,
+//!                      right_quiet_zone: 0};
 //!
 //! // Or use the constructor for defaults (you must specify the height).
 //! let png = Image::png(100);
 //! ```
 //!
+//! The height is always the one given. To use the recommended minimum height for a barcode
+//! instead, call `with_min_height`.
+//!
 //! See the README for more examples.
 
 extern crate image;
 
 use image::{ImageBuffer, Rgba, ImageRgba8, DynamicImage};
 use error::{Result, Error};
+use sym::{Barcode, helpers};
+use std::mem;
+use std::ops::Range;
  
 macro_rules! image_variants {
     ( $( #[$attr:meta] $v:ident ),* ) => {
@@ -45,6 +55,19 @@ macro_rules! image_variants {
                 foreground: Color,
                 /// The RGBA color for the background.
                 background: Color,
+                /// The number of pixels to shave off each bar (split between its two edges) to
+                /// compensate for ink spread, rounded to whole pixels and at most half the bar.
+                /// The module pitch is unchanged, so spaces widen correspondingly.
+                bar_width_reduction: f32,
+                /// Whether to invert the barcode, drawing the spaces rather than the bars in
+                /// the foreground color. Tile spacing stays background.
+                invert: bool,
+                /// The number of blank modules to leave before the barcode, in the
+                /// background color (and before the rotation is applied).
+                left_quiet_zone: u32,
+                /// The number of blank modules to leave after the barcode, as with
+                /// `left_quiet_zone`.
+                right_quiet_zone: u32,
             },
         )*
         }
@@ -59,6 +82,10 @@ macro_rules! image_defaults {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         }
     };
 }
@@ -83,7 +110,7 @@ pub struct Color {
 impl Color {
     /// Constructor.
     pub fn new(rgba: [u8; 4]) -> Color {
-        Color{rgba: rgba}
+        Color{rgba}
     }
 
     /// Constructor for black (#000000).
@@ -96,7 +123,7 @@ impl Color {
         Color::new([255, 255, 255, 255])
     }
 
-    fn to_rgba(&self) -> Rgba<u8> {
+    fn to_rgba(self) -> Rgba<u8> {
         Rgba(self.rgba)
     }
 }
@@ -114,6 +141,64 @@ pub enum Rotation {
     TwoSeventy,
 }
 
+/// Specifies where human-readable text is placed relative to the bars.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TextPosition {
+    /// The text is drawn beneath the bars. This is the default.
+    Below,
+    /// The text is drawn above the bars.
+    Above,
+    /// No text is drawn.
+    None,
+}
+
+/// Options for the human-readable text drawn by `Image::generate_with_text`.
+#[derive(Copy, Clone, Debug)]
+pub struct TextOptions {
+    /// Where the text is placed.
+    pub position: TextPosition,
+    /// The RGBA color for the text, independent of the bars.
+    pub color: Color,
+    /// The height reserved for the text, in pixels. The built-in font is scaled up by whole
+    /// multiples of its 5-pixel height to fit.
+    pub size: u32,
+}
+
+impl Default for TextOptions {
+    fn default() -> TextOptions {
+        TextOptions {
+            position: TextPosition::Below,
+            color: Color::black(),
+            size: 10,
+        }
+    }
+}
+
+const GLYPH_WIDTH: u32 = 3;
+const GLYPH_HEIGHT: u32 = 5;
+
+// Returns the rows of the built-in 3x5 bitmap glyph for the given character, most significant
+// bit leftmost. Only digits, spaces, hyphens and the EAN quiet zone markers are available.
+fn glyph(c: char) -> Option<[u8; 5]> {
+    match c {
+        '0' => Some([7, 5, 5, 5, 7]),
+        '1' => Some([2, 6, 2, 2, 7]),
+        '2' => Some([7, 1, 7, 4, 7]),
+        '3' => Some([7, 1, 7, 1, 7]),
+        '4' => Some([5, 5, 7, 1, 1]),
+        '5' => Some([7, 4, 7, 1, 7]),
+        '6' => Some([7, 4, 7, 5, 7]),
+        '7' => Some([7, 1, 1, 1, 1]),
+        '8' => Some([7, 5, 7, 5, 7]),
+        '9' => Some([7, 5, 7, 1, 7]),
+        ' ' => Some([0, 0, 0, 0, 0]),
+        '-' => Some([0, 0, 7, 0, 0]),
+        '<' => Some([1, 2, 4, 2, 1]),
+        '>' => Some([4, 2, 1, 2, 4]),
+        _ => None,
+    }
+}
+
 image_variants![
     /// GIF image generator type.
     GIF,
@@ -146,9 +231,162 @@ impl Image {
         image_defaults!(ImageBuffer, height)
     }
 
+    /// Returns a copy of this image with its height set to the recommended minimum height for
+    /// the given barcode at the current X dimension.
+    ///
+    /// This is opt-in: the constructors always take an explicit height, which is never
+    /// replaced by the recommendation unless this method is called.
+    pub fn with_min_height<B: Barcode + ?Sized>(mut self, barcode: &B) -> Image {
+        let modules = barcode.encode().len() as u32;
+
+        match self {
+            Image::GIF{ref mut height, xdim, ..} |
+            Image::PNG{ref mut height, xdim, ..} |
+            Image::JPEG{ref mut height, xdim, ..} |
+            Image::ImageBuffer{ref mut height, xdim, ..} => *height = barcode.min_height(modules * xdim),
+        }
+
+        self
+    }
+
+    /// Returns a copy of this image with both quiet zones set to the symmetric value
+    /// recommended for the given barcode (see `Barcode::metadata`).
+    pub fn with_quiet_zone<B: Barcode + ?Sized>(mut self, barcode: &B) -> Image {
+        let modules = barcode.metadata().quiet_zone_modules as u32;
+
+        match self {
+            Image::GIF{ref mut left_quiet_zone, ref mut right_quiet_zone, ..} |
+            Image::PNG{ref mut left_quiet_zone, ref mut right_quiet_zone, ..} |
+            Image::JPEG{ref mut left_quiet_zone, ref mut right_quiet_zone, ..} |
+            Image::ImageBuffer{ref mut left_quiet_zone, ref mut right_quiet_zone, ..} => {
+                *left_quiet_zone = modules;
+                *right_quiet_zone = modules;
+            }
+        }
+
+        self
+    }
+
+    /// Returns a copy of this image with the largest X dimension at which the given barcode fits
+    /// within `width_mm` millimeters when printed at `dpi` pixels per inch, including the quiet
+    /// zones. The X dimension is never less than 1 pixel.
+    ///
+    /// As the X dimension is a whole number of pixels, it is rounded down (so the barcode is
+    /// never wider than requested, unless even 1 pixel is too wide). Every bar edge then falls
+    /// on a pixel boundary, so no `snap_to_pixel` option is needed here, unlike the tiny-skia
+    /// generator's fractional X dimensions.
+    pub fn with_physical_width<B: Barcode + ?Sized>(mut self, barcode: &B, width_mm: f64, dpi: u32) -> Image {
+        let modules = self.quiet_modules(barcode);
+
+        match self {
+            Image::GIF{ref mut xdim, ..} |
+            Image::PNG{ref mut xdim, ..} |
+            Image::JPEG{ref mut xdim, ..} |
+            Image::ImageBuffer{ref mut xdim, ..} => *xdim = helpers::xdim_from_physical(modules, width_mm, dpi),
+        }
+
+        self
+    }
+
+    /// Returns the printed width of the given barcode in millimeters, at `dpi` pixels per inch,
+    /// including the quiet zones. Rotation is not taken into account.
+    pub fn total_width_mm<B: Barcode + ?Sized>(&self, barcode: &B, dpi: u32) -> f64 {
+        let xdim = expand_image_variants!(*self, {xdim: x, ..} => x, GIF, PNG, JPEG, ImageBuffer);
+
+        helpers::width_mm(self.quiet_modules(barcode), xdim, dpi)
+    }
+
+    // Returns the number of modules in the given barcode along with its quiet zones.
+    fn quiet_modules<B: Barcode + ?Sized>(&self, barcode: &B) -> usize {
+        let (left, right) = expand_image_variants!(
+            *self,
+            {left_quiet_zone: lq, right_quiet_zone: rq, ..} => (lq, rq),
+            GIF, PNG, JPEG, ImageBuffer
+        );
+
+        barcode.encode().len() + (left + right) as usize
+    }
+
     /// Generates the given barcode. Returns a `Result<Vec<u8>, Error>` of the encoded bytes or
     /// an error message.
     pub fn generate<T: AsRef<[u8]>>(&self, barcode: T) -> Result<Vec<u8>> {
+        self.generate_with_colors(barcode, &[])
+    }
+
+    /// Generates the given barcode directly from the symbology. Human-readable text is not
+    /// drawn (see `generate_with_text`), so guards are drawn at the same height as the other
+    /// bars.
+    /// Returns a `Result<Vec<u8>, Error>` of the encoded bytes or an error message.
+    pub fn generate_from<B: Barcode + ?Sized>(&self, barcode: &B) -> Result<Vec<u8>> {
+        self.generate(barcode.encode())
+    }
+
+    /// Generates the given barcode, drawing the bars within each range of module indices in the
+    /// given color rather than the foreground color. Where ranges overlap, the last one wins.
+    /// Returns a `Result<Vec<u8>, Error>` of the encoded bytes or an error message.
+    pub fn generate_with_colors<T: AsRef<[u8]>>(&self, barcode: T, colors: &[(Range<usize>, Color)]) -> Result<Vec<u8>> {
+        let img = self.place_pixels(&barcode, colors);
+
+        self.write_image(&img)
+    }
+
+    /// Generates the given matrix (or stacked) barcode, given as rows of binary digits from top
+    /// to bottom, with each module a square ```xdim``` pixels wide. The height and bar width
+    /// reduction are ignored, while the colors, inversion, quiet zones (to the left and right)
+    /// and rotation apply as usual.
+    /// Returns a `Result<Vec<u8>, Error>` of the encoded bytes, failing if the rows are of
+    /// differing lengths.
+    pub fn generate_matrix(&self, matrix: &[Vec<u8>]) -> Result<Vec<u8>> {
+        let img = self.place_matrix(matrix)?;
+
+        self.write_image(&img)
+    }
+
+    /// Generates the given matrix barcode, as with `generate_matrix`, to an image::ImageBuffer.
+    /// Returns a `Result<ImageBuffer<Rgba<u8>, Vec<u8>>, Error>` of the encoded bytes or an
+    /// error message.
+    pub fn generate_matrix_buffer(&self, matrix: &[Vec<u8>]) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+        self.place_matrix(matrix).map(|img| img.to_rgba())
+    }
+
+    /// Lays out the given barcodes in a grid with the given number of columns, returning a
+    /// single image. Each cell is as large as the largest (rotated) barcode, and `spacing`
+    /// pixels separate the cells from each other and from the edges of the sheet.
+    /// Returns a `Result<Vec<u8>, Error>` of the encoded bytes or an error message.
+    pub fn tile<T: AsRef<[u8]>>(&self, barcodes: &[T], columns: usize, spacing: u32) -> Result<Vec<u8>> {
+        let img = self.place_tiles(barcodes, columns, spacing)?;
+
+        self.write_image(&img)
+    }
+
+    /// Lays out the given barcodes in a grid, as with `tile`, to an image::ImageBuffer.
+    /// Returns a `Result<ImageBuffer<Rgba<u8>, Vec<u8>>, Error>` of the encoded bytes or an
+    /// error message.
+    pub fn tile_buffer<T: AsRef<[u8]>>(&self, barcodes: &[T], columns: usize, spacing: u32) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+        self.place_tiles(barcodes, columns, spacing).map(|img| img.to_rgba())
+    }
+
+    /// Generates the given barcode with human-readable text above or below the bars, drawn in
+    /// a built-in bitmap font. Each piece of text is centered on the given range of module
+    /// indices, which allows split layouts such as the EAN-13 digits between its guards. The
+    /// image grows by the text size plus a quarter of it as a gap, before any rotation.
+    /// Returns a `Result<Vec<u8>, Error>` of the encoded bytes, failing with `Error::Character`
+    /// if the text has characters other than digits, spaces, hyphens, `<` and `>`.
+    pub fn generate_with_text<T: AsRef<[u8]>>(&self, barcode: T, text: &[(Range<usize>, &str)], options: TextOptions) -> Result<Vec<u8>> {
+        let img = self.place_text(barcode.as_ref(), text, options)?;
+
+        self.write_image(&img)
+    }
+
+    /// Generates the given barcode with human-readable text, as with `generate_with_text`, to
+    /// an image::ImageBuffer.
+    /// Returns a `Result<ImageBuffer<Rgba<u8>, Vec<u8>>, Error>` of the encoded bytes or an
+    /// error message.
+    pub fn generate_buffer_with_text<T: AsRef<[u8]>>(&self, barcode: T, text: &[(Range<usize>, &str)], options: TextOptions) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+        self.place_text(barcode.as_ref(), text, options).map(|img| img.to_rgba())
+    }
+
+    fn write_image(&self, img: &DynamicImage) -> Result<Vec<u8>> {
         let format = match *self {
             Image::GIF{..} => image::GIF,
             Image::PNG{..} => image::PNG,
@@ -156,7 +394,6 @@ impl Image {
             _ => return Err(Error::Generate)
         };
         let mut bytes: Vec<u8> = vec![];
-        let img = self.place_pixels(&barcode);
 
         match img.write_to(&mut bytes, format) {
             Ok(_) => Ok(bytes),
@@ -167,39 +404,243 @@ impl Image {
     /// Generates the given barcode to an image::ImageBuffer. Returns a `Result<ImageBuffer<Rgba<u8>, Vec<u8>>, Error>`
     /// of the encoded bytes or an error message.
     pub fn generate_buffer<T: AsRef<[u8]>>(&self, barcode: T) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>> {
-        let img = self.place_pixels(&barcode);
+        self.generate_buffer_with_colors(barcode, &[])
+    }
+
+    /// Generates the given barcode to an image::ImageBuffer, drawing the bars within each range
+    /// of module indices in the given color rather than the foreground color. Where ranges
+    /// overlap, the last one wins.
+    /// Returns a `Result<ImageBuffer<Rgba<u8>, Vec<u8>>, Error>` of the encoded bytes or an
+    /// error message.
+    pub fn generate_buffer_with_colors<T: AsRef<[u8]>>(&self, barcode: T, colors: &[(Range<usize>, Color)]) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+        let img = self.place_pixels(&barcode, colors);
 
         Ok(img.to_rgba())
     }
 
-    fn place_pixels<T: AsRef<[u8]>>(&self, barcode: T) -> DynamicImage {
+    fn place_pixels<T: AsRef<[u8]>>(&self, barcode: T, colors: &[(Range<usize>, Color)]) -> DynamicImage {
         let barcode = barcode.as_ref();
-        let (xdim, height, rotation, bg, fg) = expand_image_variants!(
+        let (xdim, height, rotation, bg, fg, bwr, invert, (left, right)) = expand_image_variants!(
             *self,
-            {height: h, xdim: x, rotation: r, background: b, foreground: f} => (x, h, r, b.to_rgba(), f.to_rgba()),
+            {height: h, xdim: x, rotation: r, background: b, foreground: f, bar_width_reduction: w, invert: i,
+             left_quiet_zone: lq, right_quiet_zone: rq} => (x, h, r, b.to_rgba(), f.to_rgba(), w, i, (lq, rq)),
             GIF, PNG, JPEG, ImageBuffer
         );
+        let inverted;
+        let barcode = if invert {
+            inverted = helpers::invert_modules(barcode);
+            &inverted[..]
+        } else {
+            barcode
+        };
+        // The quiet zones are added after inverting, so that they stay background.
+        let padded = [&vec![0; left as usize][..], barcode, &vec![0; right as usize][..]].concat();
+        let barcode = &padded[..];
         let width = (barcode.len() as u32) * xdim;
         let mut buffer = ImageBuffer::new(width, height);
+        let mut row = Vec::with_capacity(width as usize);
+
+        for (i, &b) in barcode.iter().enumerate() {
+            let c = if b == 0 {
+                bg
+            } else {
+                colors.iter()
+                      .rev()
+                      .find(|&(r, _)| r.contains(&(i - left as usize)))
+                      .map_or(fg, |&(_, c)| c.to_rgba())
+            };
+
+            for _ in 0..xdim {
+                row.push(c);
+            }
+        }
+
+        self.reduce_bars(barcode, &mut row, xdim, bwr, bg);
 
         for y in 0..height {
-            for (i, &b) in barcode.iter().enumerate() {
-                let c = if b == 0 { bg } else { fg };
+            for (x, &c) in row.iter().enumerate() {
+                buffer.put_pixel(x as u32, y, c);
+            }
+        }
+
+        let img = ImageRgba8(buffer);
+
+        match rotation {
+            Rotation::Ninety => img.rotate90(),
+            Rotation::OneEighty => img.rotate180(),
+            Rotation::TwoSeventy => img.rotate270(),
+            _ => img,
+        }
+    }
+
+    // Shaves the given number of pixels off each bar (each run of dark modules) in the row,
+    // never removing more than half of the bar.
+    fn reduce_bars(&self, barcode: &[u8], row: &mut [Rgba<u8>], xdim: u32, reduction: f32, bg: Rgba<u8>) {
+        let reduction = reduction.round().max(0.0) as usize;
+        let xdim = xdim as usize;
+        let mut i = 0;
+
+        if reduction == 0 {
+            return;
+        }
+
+        while i < barcode.len() {
+            if barcode[i] == 0 {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            while i < barcode.len() && barcode[i] != 0 {
+                i += 1;
+            }
+
+            let bar_width = (i - start) * xdim;
+            let shaved = reduction.min(bar_width / 2);
+            let left = start * xdim;
+            let right = (i * xdim) - (shaved - (shaved / 2));
+
+            for c in &mut row[left..(left + (shaved / 2))] {
+                *c = bg;
+            }
+            for c in &mut row[right..(i * xdim)] {
+                *c = bg;
+            }
+        }
+    }
+
+    fn place_matrix(&self, matrix: &[Vec<u8>]) -> Result<DynamicImage> {
+        if matrix.windows(2).any(|w| w[0].len() != w[1].len()) {
+            return Err(Error::Generate);
+        }
+
+        // Each row is drawn unrotated, one module high, and the whole matrix rotated after.
+        let mut row_image = *self;
+        let rotation = match row_image {
+            Image::GIF{ref mut height, ref mut rotation, ref mut bar_width_reduction, xdim, ..} |
+            Image::PNG{ref mut height, ref mut rotation, ref mut bar_width_reduction, xdim, ..} |
+            Image::JPEG{ref mut height, ref mut rotation, ref mut bar_width_reduction, xdim, ..} |
+            Image::ImageBuffer{ref mut height, ref mut rotation, ref mut bar_width_reduction, xdim, ..} => {
+                *height = xdim;
+                *bar_width_reduction = 0.0;
+                mem::replace(rotation, Rotation::Zero)
+            }
+        };
+        let rows: Vec<_> = matrix.iter()
+                                 .map(|r| row_image.place_pixels(r, &[]).to_rgba())
+                                 .collect();
+        let width = rows.first().map_or(0, |r| r.width());
+        let row_height = rows.first().map_or(0, |r| r.height());
+        let mut buffer = ImageBuffer::new(width, row_height * rows.len() as u32);
+
+        for (i, row) in rows.iter().enumerate() {
+            for (x, y, &p) in row.enumerate_pixels() {
+                buffer.put_pixel(x, y + (i as u32 * row_height), p);
+            }
+        }
+
+        let img = ImageRgba8(buffer);
+
+        Ok(match rotation {
+            Rotation::Ninety => img.rotate90(),
+            Rotation::OneEighty => img.rotate180(),
+            Rotation::TwoSeventy => img.rotate270(),
+            _ => img,
+        })
+    }
+
+    fn place_text(&self, barcode: &[u8], text: &[(Range<usize>, &str)], options: TextOptions) -> Result<DynamicImage> {
+        let glyphs = text.iter()
+                         .map(|&(ref r, t)| t.chars().map(glyph).collect::<Option<Vec<_>>>().map(|g| (r, g)))
+                         .collect::<Option<Vec<_>>>()
+                         .ok_or(Error::Character)?;
+
+        // The bars are drawn unrotated, and the whole image rotated after the text is added.
+        let mut bars_image = *self;
+        let (xdim, height, left, bg, rotation) = match bars_image {
+            Image::GIF{ref mut rotation, xdim, height, left_quiet_zone, background, ..} |
+            Image::PNG{ref mut rotation, xdim, height, left_quiet_zone, background, ..} |
+            Image::JPEG{ref mut rotation, xdim, height, left_quiet_zone, background, ..} |
+            Image::ImageBuffer{ref mut rotation, xdim, height, left_quiet_zone, background, ..} => {
+                (xdim, height, left_quiet_zone, background.to_rgba(), mem::replace(rotation, Rotation::Zero))
+            }
+        };
+        let gap = options.size / 4;
+        let (bars_y, text_y) = match options.position {
+            TextPosition::None => return Ok(self.place_pixels(barcode, &[])),
+            TextPosition::Above => (options.size + gap, 0),
+            TextPosition::Below => (0, height + gap),
+        };
+        let bars = bars_image.place_pixels(barcode, &[]).to_rgba();
+        let mut buffer = ImageBuffer::from_pixel(bars.width(), height + gap + options.size, bg);
+        let scale = (options.size / GLYPH_HEIGHT).max(1);
+        let color = options.color.to_rgba();
 
-                for p in 0..xdim {
-                    buffer.put_pixel((i as u32 * xdim) + p, y, c);
+        for (x, y, &p) in bars.enumerate_pixels() {
+            buffer.put_pixel(x, y + bars_y, p);
+        }
+
+        for (r, g) in glyphs {
+            let text_width = ((g.len() as u32 * (GLYPH_WIDTH + 1)).saturating_sub(1)) * scale;
+            let center = (left * xdim) + ((r.start + r.end) as u32 * xdim / 2);
+            let text_x = center.saturating_sub(text_width / 2);
+
+            for (n, rows) in g.iter().enumerate() {
+                for (row, bits) in rows.iter().enumerate() {
+                    for col in (0..GLYPH_WIDTH).filter(|col| bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0) {
+                        let x = text_x + (((n as u32 * (GLYPH_WIDTH + 1)) + col) * scale);
+                        let y = text_y + (row as u32 * scale);
+
+                        for (dx, dy) in (0..scale).flat_map(|dx| (0..scale).map(move |dy| (dx, dy))) {
+                            if x + dx < buffer.width() && y + dy < buffer.height() {
+                                buffer.put_pixel(x + dx, y + dy, color);
+                            }
+                        }
+                    }
                 }
             }
         }
 
         let img = ImageRgba8(buffer);
 
-        match rotation {
+        Ok(match rotation {
             Rotation::Ninety => img.rotate90(),
             Rotation::OneEighty => img.rotate180(),
             Rotation::TwoSeventy => img.rotate270(),
             _ => img,
+        })
+    }
+
+    fn place_tiles<T: AsRef<[u8]>>(&self, barcodes: &[T], columns: usize, spacing: u32) -> Result<DynamicImage> {
+        if columns == 0 {
+            return Err(Error::Generate);
         }
+
+        let bg = match *self {
+            Image::GIF{background, ..} |
+            Image::PNG{background, ..} |
+            Image::JPEG{background, ..} |
+            Image::ImageBuffer{background, ..} => background.to_rgba(),
+        };
+        let cells: Vec<_> = barcodes.iter()
+                                    .map(|b| self.place_pixels(b, &[]).to_rgba())
+                                    .collect();
+        let cell_width = cells.iter().map(|c| c.width()).max().unwrap_or(0);
+        let cell_height = cells.iter().map(|c| c.height()).max().unwrap_or(0);
+        let rows = ((cells.len() + columns - 1) / columns) as u32;
+        let columns = columns as u32;
+        let width = (columns * cell_width) + ((columns + 1) * spacing);
+        let height = (rows * cell_height) + ((rows + 1) * spacing);
+        let mut sheet = ImageBuffer::from_pixel(width, height, bg);
+
+        for (i, cell) in cells.iter().enumerate() {
+            let x = spacing + ((i as u32 % columns) * (cell_width + spacing));
+            let y = spacing + ((i as u32 / columns) * (cell_height + spacing));
+
+            image::imageops::overlay(&mut sheet, cell, x, y);
+        }
+
+        Ok(ImageRgba8(sheet))
     }
 }
 
@@ -217,6 +658,7 @@ mod tests {
     use sym::tf::*;
     use sym::codabar::*;
     use generators::image::*;
+    use error::Error;
     use std::io::prelude::*;
     use std::io::BufWriter;
     use std::fs::File;
@@ -226,13 +668,13 @@ mod tests {
     const WRITE_TO_FILE: bool = true;
 
     fn open_file(name: &'static str) -> File {
-        File::create(&Path::new(&format!("{}/{}", TEST_DATA_BASE, name)[..])).unwrap()
+        File::create(Path::new(&format!("{}/{}", TEST_DATA_BASE, name)[..])).unwrap()
     }
 
     fn write_file(bytes: &[u8], file: &'static str) {
         let path = open_file(file);
         let mut writer = BufWriter::new(path);
-        writer.write(bytes).unwrap();
+        writer.write_all(bytes).unwrap();
     }
 
     #[test]
@@ -255,6 +697,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = png.generate(&ean13.encode()[..]).unwrap();
 
@@ -272,6 +718,10 @@ mod tests {
             rotation: Rotation::Ninety,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = png.generate(&ean13.encode()[..]).unwrap();
 
@@ -289,6 +739,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = jpeg.generate(&ean13.encode()[..]).unwrap();
 
@@ -299,18 +753,92 @@ mod tests {
 
     #[test]
     fn ean_13_as_image_buffer() {
-        let ean13 = EAN13::new("7503995991130").unwrap();
+        let ean13 = EAN13::new("7503995991139").unwrap();
         let img = Image::ImageBuffer {
             height: 99,
             xdim: 1,
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = img.generate_buffer(&ean13.encode()[..]).unwrap();
 
         assert_eq!(generated.height(), 99);
-        assert_eq!(generated.width(), 102);
+        assert_eq!(generated.width(), 95);
+    }
+
+    #[test]
+    fn ean_13_as_image_buffer_with_physical_width() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let img = Image::image_buffer(40).with_physical_width(&ean13, 37.29, 600);
+        let generated = img.generate_buffer(&ean13.encode()[..]).unwrap();
+
+        assert_eq!(generated.width(), 95 * 9);
+        assert!((img.total_width_mm(&ean13, 600) - 36.195).abs() < 0.001);
+    }
+
+    #[test]
+    fn ean_13_as_image_buffer_with_physical_width_and_quiet_zones() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let img = Image::ImageBuffer {
+            height: 40,
+            xdim: 1,
+            rotation: Rotation::Zero,
+            foreground: Color::black(),
+            background: Color::white(),
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 11,
+            right_quiet_zone: 7,
+        };
+        let img = img.with_physical_width(&ean13, 37.29, 600);
+        let generated = img.generate_buffer(&ean13.encode()[..]).unwrap();
+        let width_mm = img.total_width_mm(&ean13, 600);
+
+        // The 95 modules and 18 quiet modules are 113 modules in all.
+        assert_eq!(generated.width(), 113 * 7);
+        assert!((width_mm - 33.486).abs() < 0.001);
+        assert_eq!(img.with_physical_width(&ean13, width_mm, 600).generate_buffer(&ean13.encode()[..]).unwrap().width(), 113 * 7);
+    }
+
+    #[test]
+    fn code128_as_image_buffer_with_min_height() {
+        let code128 = Code128::new("ÀHELLOWORLD").unwrap();
+        let img = Image::ImageBuffer {
+            height: 0,
+            xdim: 2,
+            rotation: Rotation::Zero,
+            foreground: Color{rgba: [0, 0, 0, 255]},
+            background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
+        };
+        let generated = img.with_min_height(&code128)
+                           .generate_buffer(&code128.encode()[..])
+                           .unwrap();
+
+        assert_eq!(generated.width(), 290);
+        assert_eq!(generated.height(), 44);
+    }
+
+    #[test]
+    fn colored_modules_ean_13_as_image_buffer() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let img = Image::image_buffer(10);
+        let red = Color::new([255, 0, 0, 255]);
+        let generated = img.generate_buffer_with_colors(&ean13.encode()[..], &[(0..3, red), (94..200, red)]).unwrap();
+
+        assert_eq!(generated.get_pixel(0, 5).0, [255, 0, 0, 255]);
+        assert_eq!(generated.get_pixel(1, 5).0, [255, 255, 255, 255]);
+        assert_eq!(generated.get_pixel(2, 5).0, [255, 0, 0, 255]);
+        assert_eq!(generated.get_pixel(4, 5).0, [0, 0, 0, 255]);
+        assert_eq!(generated.get_pixel(94, 5).0, [255, 0, 0, 255]);
     }
 
     #[test]
@@ -322,6 +850,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [255, 38, 42, 255]},
             background: Color{rgba: [34, 52, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
 
         let generated = gif.generate(&ean13.encode()[..]).unwrap();
@@ -340,6 +872,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [255, 38, 42, 120]},
             background: Color{rgba: [34, 52, 255, 120]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
 
         let generated = png.generate(&ean13.encode()[..]).unwrap();
@@ -358,6 +894,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = png.generate(&code39.encode()[..]).unwrap();
 
@@ -375,6 +915,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = gif.generate(&code39.encode()[..]).unwrap();
 
@@ -392,6 +936,10 @@ mod tests {
             rotation: Rotation::OneEighty,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = gif.generate(&code39.encode()[..]).unwrap();
 
@@ -409,6 +957,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = png.generate(&code93.encode()[..]).unwrap();
 
@@ -426,6 +978,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = gif.generate(&code93.encode()[..]).unwrap();
 
@@ -443,6 +999,10 @@ mod tests {
             rotation: Rotation::OneEighty,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = gif.generate(&code93.encode()[..]).unwrap();
 
@@ -460,6 +1020,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = png.generate(&code11.encode()[..]).unwrap();
 
@@ -477,6 +1041,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = gif.generate(&code11.encode()[..]).unwrap();
 
@@ -494,6 +1062,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = png.generate(&codabar.encode()[..]).unwrap();
 
@@ -511,6 +1083,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = gif.generate(&codabar.encode()[..]).unwrap();
 
@@ -528,6 +1104,10 @@ mod tests {
             rotation: Rotation::Ninety,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = gif.generate(&codabar.encode()[..]).unwrap();
 
@@ -545,6 +1125,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = png.generate(&code128.encode()[..]).unwrap();
 
@@ -562,6 +1146,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = gif.generate(&code128.encode()[..]).unwrap();
 
@@ -579,6 +1167,10 @@ mod tests {
             rotation: Rotation::OneEighty,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = gif.generate(&code128.encode()[..]).unwrap();
 
@@ -596,6 +1188,10 @@ mod tests {
             rotation: Rotation::OneEighty,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = img.generate_buffer(&code128.encode()[..]).unwrap();
 
@@ -612,6 +1208,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = png.generate(&ean8.encode()[..]).unwrap();
 
@@ -629,6 +1229,10 @@ mod tests {
             rotation: Rotation::TwoSeventy,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = png.generate(&ean8.encode()[..]).unwrap();
 
@@ -646,6 +1250,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = gif.generate(&ean8.encode()[..]).unwrap();
 
@@ -663,6 +1271,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = jpeg.generate(&ean8.encode()[..]).unwrap();
 
@@ -680,6 +1292,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = png.generate(&ean2.encode()[..]).unwrap();
 
@@ -697,6 +1313,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = gif.generate(&ean5.encode()[..]).unwrap();
 
@@ -714,6 +1334,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = jpeg.generate(&ean5.encode()[..]).unwrap();
 
@@ -731,6 +1355,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = img.generate_buffer(&ean5.encode()[..]).unwrap();
 
@@ -747,6 +1375,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = png.generate(&itf.encode()[..]).unwrap();
 
@@ -764,6 +1396,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = png.generate(&stf.encode()[..]).unwrap();
 
@@ -781,6 +1417,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = gif.generate(&itf.encode()[..]).unwrap();
 
@@ -798,6 +1438,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = jpeg.generate(&itf.encode()[..]).unwrap();
 
@@ -815,6 +1459,10 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
         let generated = img.generate_buffer(&itf.encode()[..]).unwrap();
 
@@ -831,8 +1479,179 @@ mod tests {
             rotation: Rotation::Zero,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         };
 
         assert!(img.generate(&itf.encode()[..]).is_err());
     }
+
+    #[test]
+    fn tiled_ean_8_as_image_buffer() {
+        let data = ["1234567", "9876543", "5512345", "1111111"];
+        let barcodes: Vec<Vec<u8>> = data.iter().map(|d| EAN8::new(d).unwrap().encode()).collect();
+        let img = Image::image_buffer(40);
+        let generated = img.tile_buffer(&barcodes, 2, 10).unwrap();
+
+        // 2 * 67 modules plus 3 gaps wide, 2 * 40 pixels plus 3 gaps high.
+        assert_eq!(generated.width(), 164);
+        assert_eq!(generated.height(), 110);
+        assert_eq!(generated.get_pixel(9, 10).0, [255, 255, 255, 255]);
+        assert_eq!(generated.get_pixel(10, 10).0, [0, 0, 0, 255]);
+        assert_eq!(generated.get_pixel(87, 60).0, [0, 0, 0, 255]);
+        assert_eq!(generated.get_pixel(87, 100).0, [255, 255, 255, 255]);
+        assert!(img.tile_buffer(&barcodes, 0, 10).is_err());
+    }
+
+    #[test]
+    fn inverted_ean_8_as_image_buffer() {
+        let ean8 = EAN8::new("1234567").unwrap();
+        let img = Image::ImageBuffer {
+            height: 20,
+            xdim: 1,
+            rotation: Rotation::Zero,
+            foreground: Color::black(),
+            background: Color::white(),
+            bar_width_reduction: 0.0,
+            invert: true,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
+        };
+        let generated = img.generate_buffer(ean8.encode()).unwrap();
+        let tiled = img.tile_buffer(&[ean8.encode()], 1, 5).unwrap();
+
+        // The first guard bar is now background, and the space after it foreground.
+        assert_eq!(generated.get_pixel(0, 10).0, [255, 255, 255, 255]);
+        assert_eq!(generated.get_pixel(1, 10).0, [0, 0, 0, 255]);
+        // The spacing around a tile is left as background.
+        assert_eq!(tiled.get_pixel(0, 10).0, [255, 255, 255, 255]);
+        assert_eq!(tiled.get_pixel(5, 10).0, [255, 255, 255, 255]);
+        assert_eq!(tiled.get_pixel(6, 10).0, [0, 0, 0, 255]);
+        assert_eq!(tiled.get_pixel(6, 2).0, [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn ean_8_with_quiet_zones_as_image_buffer() {
+        let ean8 = EAN8::new("1234567").unwrap();
+        let img = Image::ImageBuffer {
+            height: 10,
+            xdim: 2,
+            rotation: Rotation::Zero,
+            foreground: Color::black(),
+            background: Color::white(),
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 12,
+            right_quiet_zone: 6,
+        };
+        let generated = img.generate_buffer(ean8.encode()).unwrap();
+        let symmetric = Image::image_buffer(10).with_quiet_zone(&ean8).generate_buffer(ean8.encode()).unwrap();
+
+        assert_eq!(generated.width(), (12 + 67 + 6) * 2);
+        assert_eq!(generated.get_pixel(23, 5).0, [255, 255, 255, 255]);
+        assert_eq!(generated.get_pixel(24, 5).0, [0, 0, 0, 255]);
+        assert_eq!(generated.get_pixel(157, 5).0, [0, 0, 0, 255]);
+        assert_eq!(generated.get_pixel(158, 5).0, [255, 255, 255, 255]);
+        // EAN-8 recommends 7 modules on each side.
+        assert_eq!(symmetric.width(), 7 + 67 + 7);
+    }
+
+    #[test]
+    fn ean_8_with_text_as_image_buffer() {
+        let ean8 = EAN8::new("1234567").unwrap();
+        let img = Image::image_buffer(20);
+        let red = Color::new([255, 0, 0, 255]);
+        let text = [(3..31, "1234"), (36..64, "5670")];
+        let below = img.generate_buffer_with_text(ean8.encode(), &text, TextOptions{color: red, ..TextOptions::default()}).unwrap();
+        let above = img.generate_buffer_with_text(ean8.encode(), &text, TextOptions{position: TextPosition::Above, ..TextOptions::default()}).unwrap();
+        let none = img.generate_buffer_with_text(ean8.encode(), &text, TextOptions{position: TextPosition::None, ..TextOptions::default()}).unwrap();
+
+        // 20 pixels of bars, a 2 pixel gap and 10 pixels of text, scaled twice.
+        assert_eq!((below.width(), below.height()), (67, 32));
+        assert_eq!(below.get_pixel(0, 0).0, [0, 0, 0, 255]);
+        assert_eq!(below.get_pixel(2, 22).0, [255, 255, 255, 255]);
+        assert_eq!(below.get_pixel(4, 22).0, [255, 0, 0, 255]);
+        assert_eq!((above.width(), above.height()), (67, 32));
+        assert_eq!(above.get_pixel(0, 11).0, [255, 255, 255, 255]);
+        assert_eq!(above.get_pixel(0, 12).0, [0, 0, 0, 255]);
+        assert_eq!(above.get_pixel(4, 0).0, [0, 0, 0, 255]);
+        assert_eq!((none.width(), none.height()), (67, 20));
+    }
+
+    #[test]
+    fn invalid_text_as_image_buffer() {
+        let ean8 = EAN8::new("1234567").unwrap();
+        let img = Image::image_buffer(20);
+        let generated = img.generate_buffer_with_text(ean8.encode(), &[(3..31, "12A4")], TextOptions::default());
+
+        assert_eq!(generated.err().unwrap(), Error::Character);
+    }
+
+    #[test]
+    fn checkerboard_as_image_buffer() {
+        let matrix = vec![vec![1, 0, 1], vec![0, 1, 0], vec![1, 0, 1]];
+        let img = Image::ImageBuffer {
+            height: 100,
+            xdim: 4,
+            rotation: Rotation::Zero,
+            foreground: Color::new([255, 0, 0, 255]),
+            background: Color::new([0, 0, 255, 128]),
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 1,
+            right_quiet_zone: 0,
+        };
+        let generated = img.generate_matrix_buffer(&matrix).unwrap();
+
+        // Square modules, with the quiet zone on the left. The height is ignored.
+        assert_eq!(generated.dimensions(), (16, 12));
+
+        for (y, row) in matrix.iter().enumerate() {
+            for (x, &m) in row.iter().enumerate() {
+                let expected = if m == 1 { [255, 0, 0, 255] } else { [0, 0, 255, 128] };
+
+                assert_eq!(generated.get_pixel(4 + (x as u32 * 4) + 2, (y as u32 * 4) + 2).0, expected);
+            }
+        }
+
+        assert_eq!(generated.get_pixel(2, 2).0, [0, 0, 255, 128]);
+        assert!(Image::png(10).generate_matrix(&matrix).is_ok());
+        assert_eq!(img.generate_matrix_buffer(&[vec![1, 0], vec![1]]).err(), Some(Error::Generate));
+    }
+
+    #[test]
+    fn tiled_ean_8_as_png() {
+        let data = ["1234567", "9876543", "5512345", "1111111"];
+        let barcodes: Vec<Vec<u8>> = data.iter().map(|d| EAN8::new(d).unwrap().encode()).collect();
+        let png = Image::png(40);
+        let generated = png.tile(&barcodes, 3, 5).unwrap();
+
+        if WRITE_TO_FILE { write_file(&generated[..], "ean8_tiled.png"); }
+
+        assert!(Image::image_buffer(40).tile(&barcodes, 3, 5).is_err());
+    }
+
+    #[test]
+    fn reduced_ean_13_as_image_buffer() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let img = Image::ImageBuffer {
+            height: 10,
+            xdim: 4,
+            rotation: Rotation::Zero,
+            foreground: Color{rgba: [0, 0, 0, 255]},
+            background: Color{rgba: [255, 255, 255, 255]},
+            bar_width_reduction: 2.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
+        };
+        let generated = img.generate_buffer(&ean13.encode()[..]).unwrap();
+        let row: Vec<u8> = (0..12).map(|x| generated.get_pixel(x, 5).0[0]).collect();
+
+        // Each guard bar loses a pixel from either edge; the module pitch is unchanged.
+        assert_eq!(generated.width(), 380);
+        assert_eq!(row, vec![255, 0, 0, 255, 255, 255, 255, 255, 255, 0, 0, 255]);
+    }
 }
@@ -5,13 +5,36 @@
 //! Output will be of the format:
 //! ```javascript
 //! {
+//!   "schema_version": 2,
+//!   "dimensions": "1d",
 //!   "height": 10,
 //!   "xdim": 1,
 //!   "encoding": [1, 0, 0, 1, 1, 0, ...],
 //! }
 //! ```
-
-use error::Result;
+//!
+//! Matrix (or stacked) barcodes are generated with `JSON::generate_matrix`, in which case the
+//! dimensions are `"2d"` and the encoding holds one array per row. The schema version is bumped
+//! whenever the format changes, so that consumers can tell the formats apart. Version 1 (which
+//! had no `schema_version` field) only supported 1D barcodes.
+
+use error::{Error, Result};
+use sym::Barcode;
+
+// The version of the output format.
+const SCHEMA_VERSION: u32 = 2;
+
+// Formats the binary digits as a JSON array.
+fn array(modules: &[u8]) -> String {
+    let bits: Vec<&str> = modules.iter()
+                                 .map(|&b| match b {
+                                     0 => "0",
+                                     _ => "1",
+                                 })
+                                 .collect();
+
+    format!("[{}]", bits.join(","))
+}
 
 /// The JSON  barcode generator type.
 #[derive(Copy, Clone, Debug)]
@@ -22,6 +45,12 @@ pub struct JSON {
     pub xdim: usize,
 }
 
+impl Default for JSON {
+    fn default() -> JSON {
+        JSON::new()
+    }
+}
+
 impl JSON {
     /// Returns a new JSON with default values.
     pub fn new() -> JSON {
@@ -33,25 +62,31 @@ impl JSON {
 
     /// Generates the given barcode. Returns a `Result<String, Error>` indicating success.
     pub fn generate<T: AsRef<[u8]>>(&self, barcode: T) -> Result<String> {
-        let mut bits = barcode
-            .as_ref()
-            .iter()
-            .fold(String::new(), |acc, &b| {
-                let n = match b {
-                    0 => "0",
-                    _ => "1",
-                };
+        Ok(self.document("1d", &array(barcode.as_ref())))
+    }
 
-                acc + n + ","
-            });
+    /// Generates the given matrix (or stacked) barcode, given as rows from top to bottom. The
+    /// height is that of each row. Returns a `Result<String, Error>` indicating success, failing
+    /// if the rows are of differing lengths.
+    pub fn generate_matrix(&self, rows: &[Vec<u8>]) -> Result<String> {
+        if rows.windows(2).any(|w| w[0].len() != w[1].len()) {
+            return Err(Error::Generate);
+        }
 
-        // Kill trailing comma.
-        bits.pop();
+        let arrays: Vec<String> = rows.iter().map(|r| array(r)).collect();
 
-        let output = format!("{{\"height\":{},\"xdim\":{},\"encoding\":[{}]}}",
-                             self.height, self.xdim, bits);
+        Ok(self.document("2d", &format!("[{}]", arrays.join(","))))
+    }
+
+    fn document(&self, dimensions: &str, encoding: &str) -> String {
+        format!("{{\"schema_version\":{},\"dimensions\":\"{}\",\"height\":{},\"xdim\":{},\"encoding\":{}}}",
+                SCHEMA_VERSION, dimensions, self.height, self.xdim, encoding)
+    }
 
-        Ok(output)
+    /// Generates the given barcode directly from the symbology. Returns a
+    /// `Result<String, Error>` indicating success.
+    pub fn generate_from<B: Barcode + ?Sized>(&self, barcode: &B) -> Result<String> {
+        self.generate(barcode.encode())
     }
 }
 
@@ -66,7 +101,19 @@ mod tests {
     use ::sym::code128::*;
     use ::sym::tf::*;
     use ::sym::codabar::*;
+    use ::sym::codablock::*;
     use ::generators::json::*;
+    use error::Error;
+
+    // Finds the raw value of a top-level field, which is enough to check the fields before the
+    // encoding.
+    fn field<'a>(json: &'a str, name: &str) -> &'a str {
+        let key = format!("\"{}\":", name);
+        let start = json.find(&key).unwrap() + key.len();
+        let end = start + json[start..].find([',', '}']).unwrap();
+
+        &json[start..end]
+    }
 
     #[test]
     fn ean_13_as_json() {
@@ -74,7 +121,36 @@ mod tests {
         let json = JSON::new();
         let generated = json.generate(&ean13.encode()[..]).unwrap();
 
-        assert_eq!(generated, "{\"height\":10,\"xdim\":1,\"encoding\":[1,0,1,0,1,1,0,0,0,1,0,1,0,0,1,1,1,0,0,1,1,0,0,1,0,1,0,0,1,1,1,0,1,1,1,1,0,1,0,1,1,0,0,1,1,0,1,0,1,0,1,0,0,0,0,1,0,1,1,0,0,1,1,0,1,1,0,0,1,1,0,1,0,0,0,0,1,0,1,1,1,0,0,1,0,1,1,1,0,1,0,0,1,0,1]}".trim());
+        assert_eq!(generated, "{\"schema_version\":2,\"dimensions\":\"1d\",\"height\":10,\"xdim\":1,\"encoding\":[1,0,1,0,1,1,0,0,0,1,0,1,0,0,1,1,1,0,0,1,1,0,0,1,0,1,0,0,1,1,1,0,1,1,1,1,0,1,0,1,1,0,0,1,1,0,1,0,1,0,1,0,0,0,0,1,0,1,1,0,0,1,1,0,1,1,0,0,1,1,0,1,0,0,0,0,1,0,1,1,1,0,0,1,0,1,1,1,0,1,0,0,1,0,1]}".trim());
+    }
+
+    #[test]
+    fn ean_13_as_json_fields() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let generated = JSON::new().generate(ean13.encode()).unwrap();
+
+        assert_eq!(field(&generated, "schema_version"), "2");
+        assert_eq!(field(&generated, "dimensions"), "\"1d\"");
+        assert!(generated.ends_with(&format!("\"encoding\":{}}}", array(&ean13.encode()))));
+    }
+
+    #[test]
+    fn codablock_f_as_json_matrix() {
+        let codablock = CodablockF::with_columns("CODABLOCK", 6).unwrap();
+        let rows = codablock.encode();
+        let generated = JSON{height: 8, xdim: 2}.generate_matrix(&rows).unwrap();
+        let encoding = &generated[generated.find("\"encoding\":").unwrap() + 11..generated.len() - 1];
+
+        assert_eq!(field(&generated, "schema_version"), "2");
+        assert_eq!(field(&generated, "dimensions"), "\"2d\"");
+        assert_eq!(field(&generated, "height"), "8");
+        // One array per row, each as long as the row.
+        assert!(encoding.starts_with("[[1,1,0,1,0,0,0,0,1,0,0,"));
+        assert_eq!(encoding.matches('[').count(), rows.len() + 1);
+        assert_eq!(encoding.split("],[").map(|r| r.matches(['0', '1']).count()).collect::<Vec<_>>(),
+                   vec![rows[0].len(), rows[1].len()]);
+        assert_eq!(JSON::new().generate_matrix(&[]).unwrap(), "{\"schema_version\":2,\"dimensions\":\"2d\",\"height\":10,\"xdim\":1,\"encoding\":[]}");
+        assert_eq!(JSON::new().generate_matrix(&[vec![1], vec![1, 0]]).err(), Some(Error::Generate));
     }
 
     #[test]
@@ -83,7 +159,7 @@ mod tests {
         let json = JSON{height: 6, xdim: 2};
         let generated = json.generate(&ean13.encode()[..]).unwrap();
 
-        assert_eq!(generated, "{\"height\":6,\"xdim\":2,\"encoding\":[1,0,1,0,1,1,0,0,0,1,0,1,0,0,1,1,1,0,0,1,1,0,0,1,0,1,0,0,1,1,1,0,1,1,1,1,0,1,0,1,1,0,0,1,1,0,1,0,1,0,1,0,0,0,0,1,0,1,1,0,0,1,1,0,1,1,0,0,1,1,0,1,0,0,0,0,1,0,1,1,1,0,0,1,0,1,1,1,0,1,0,0,1,0,1]}".trim());
+        assert_eq!(generated, "{\"schema_version\":2,\"dimensions\":\"1d\",\"height\":6,\"xdim\":2,\"encoding\":[1,0,1,0,1,1,0,0,0,1,0,1,0,0,1,1,1,0,0,1,1,0,0,1,0,1,0,0,1,1,1,0,1,1,1,1,0,1,0,1,1,0,0,1,1,0,1,0,1,0,1,0,0,0,0,1,0,1,1,0,0,1,1,0,1,1,0,0,1,1,0,1,0,0,0,0,1,0,1,1,1,0,0,1,0,1,1,1,0,1,0,0,1,0,1]}".trim());
     }
 
     #[test]
@@ -92,7 +168,7 @@ mod tests {
         let json = JSON::new();
         let generated = json.generate(&ean8.encode()[..]).unwrap();
 
-        assert_eq!(generated, "{\"height\":10,\"xdim\":1,\"encoding\":[1,0,1,0,0,1,1,0,0,1,0,0,1,0,0,1,1,0,1,1,1,1,0,1,0,1,0,0,0,1,1,0,1,0,1,0,1,0,0,1,1,1,0,1,0,1,0,0,0,0,1,0,0,0,1,0,0,1,1,1,0,0,1,0,1,0,1]}".trim());
+        assert_eq!(generated, "{\"schema_version\":2,\"dimensions\":\"1d\",\"height\":10,\"xdim\":1,\"encoding\":[1,0,1,0,0,1,1,0,0,1,0,0,1,0,0,1,1,0,1,1,1,1,0,1,0,1,0,0,0,1,1,0,1,0,1,0,1,0,0,1,1,1,0,1,0,1,0,0,0,0,1,0,0,0,1,0,0,1,1,1,0,0,1,0,1,0,1]}".trim());
     }
 
     #[test]
@@ -101,7 +177,7 @@ mod tests {
         let json = JSON{height: 5, xdim: 2};
         let generated = json.generate(&ean8.encode()[..]).unwrap();
 
-        assert_eq!(generated, "{\"height\":5,\"xdim\":2,\"encoding\":[1,0,1,0,0,1,1,0,0,1,0,0,1,0,0,1,1,0,1,1,1,1,0,1,0,1,0,0,0,1,1,0,1,0,1,0,1,0,0,1,1,1,0,1,0,1,0,0,0,0,1,0,0,0,1,0,0,1,1,1,0,0,1,0,1,0,1]}".trim());
+        assert_eq!(generated, "{\"schema_version\":2,\"dimensions\":\"1d\",\"height\":5,\"xdim\":2,\"encoding\":[1,0,1,0,0,1,1,0,0,1,0,0,1,0,0,1,1,0,1,1,1,1,0,1,0,1,0,0,0,1,1,0,1,0,1,0,1,0,0,1,1,1,0,1,0,1,0,0,0,0,1,0,0,0,1,0,0,1,1,1,0,0,1,0,1,0,1]}".trim());
     }
 
     #[test]
@@ -110,7 +186,7 @@ mod tests {
         let json = JSON::new();
         let generated = json.generate(&code93.encode()[..]).unwrap();
 
-        assert_eq!(generated, "{\"height\":10,\"xdim\":1,\"encoding\":[1,0,1,0,1,1,1,1,0,1,0,1,0,0,1,1,0,0,1,0,0,1,0,1,1,0,0,1,0,1,0,0,0,1,1,0,1,0,0,0,1,1,0,1,0,1,1,0,0,1,0,0,1,0,1,0,0,1,1,0,1,1,0,1,0,1,0,0,1,1,0,0,1,1,0,1,0,1,0,0,0,1,0,1,1,0,1,0,0,0,1,0,1,1,0,0,0,1,0,1,1,0,1,0,0,0,1,0,1,0,0,1,1,0,1,1,0,1,0,1,0,0,1,0,0,0,1,0,1,0,1,1,1,1,0,1]}".trim());
+        assert_eq!(generated, "{\"schema_version\":2,\"dimensions\":\"1d\",\"height\":10,\"xdim\":1,\"encoding\":[1,0,1,0,1,1,1,1,0,1,0,1,0,0,1,1,0,0,1,0,0,1,0,1,1,0,0,1,0,1,0,0,0,1,1,0,1,0,0,0,1,1,0,1,0,1,1,0,0,1,0,0,1,0,1,0,0,1,1,0,1,1,0,1,0,1,0,0,1,1,0,0,1,1,0,1,0,1,0,0,0,1,0,1,1,0,1,0,0,0,1,0,1,1,0,0,0,1,0,1,1,0,1,0,0,0,1,0,1,0,0,1,1,0,1,1,0,1,0,1,0,0,1,0,0,0,1,0,1,0,1,1,1,1,0,1]}".trim());
     }
 
     #[test]
@@ -119,7 +195,7 @@ mod tests {
         let json = JSON{height: 7, xdim: 2};
         let generated = json.generate(&code93.encode()[..]).unwrap();
 
-        assert_eq!(generated, "{\"height\":7,\"xdim\":2,\"encoding\":[1,0,1,0,1,1,1,1,0,1,0,1,0,0,1,0,0,0,1,0,1,0,0,0,1,0,0,1,0,1,0,0,0,0,1,0,1,0,0,1,0,1,0,0,0,1,0,0,0,1,1,0,1,0,1,0,1,0,0,0,0,1,0,1,0,1,0,1,1,1,1,0,1]}".trim());
+        assert_eq!(generated, "{\"schema_version\":2,\"dimensions\":\"1d\",\"height\":7,\"xdim\":2,\"encoding\":[1,0,1,0,1,1,1,1,0,1,0,1,0,0,1,0,0,0,1,0,1,0,0,0,1,0,0,1,0,1,0,0,0,0,1,0,1,0,0,1,0,1,0,0,0,1,0,0,0,1,1,0,1,0,1,0,1,0,0,0,0,1,0,1,0,1,0,1,1,1,1,0,1]}".trim());
     }
 
     #[test]
@@ -128,7 +204,7 @@ mod tests {
         let json = JSON::new();
         let generated = json.generate(&code39.encode()[..]).unwrap();
 
-        assert_eq!(generated, "{\"height\":10,\"xdim\":1,\"encoding\":[1,0,0,1,0,1,1,0,1,1,0,1,0,1,0,1,0,1,1,0,1,1,0,0,1,0,1,1,0,1,0,1,1,0,0,1,0,1,0,1,0,1,1,0,1,0,1,1,0,0,1,0,1,0,1,0,1,1,0,1,1,0,0,1,0,1,1,0,1,0,0,1,0,1,1,0,1,0,1,0,1,0,0,1,1,0,1,1,0,1,0,1,1,0,1,0,0,1,1,0,1,0,1,0,1,0,1,1,0,0,1,0,1,0,1,1,0,1,0,0,1,0,1,1,0,1,1,0,1]}".trim());
+        assert_eq!(generated, "{\"schema_version\":2,\"dimensions\":\"1d\",\"height\":10,\"xdim\":1,\"encoding\":[1,0,0,1,0,1,1,0,1,1,0,1,0,1,0,1,0,1,1,0,1,1,0,0,1,0,1,1,0,1,0,1,1,0,0,1,0,1,0,1,0,1,1,0,1,0,1,1,0,0,1,0,1,0,1,0,1,1,0,1,1,0,0,1,0,1,1,0,1,0,0,1,0,1,1,0,1,0,1,0,1,0,0,1,1,0,1,1,0,1,0,1,1,0,1,0,0,1,1,0,1,0,1,0,1,0,1,1,0,0,1,0,1,0,1,1,0,1,0,0,1,0,1,1,0,1,1,0,1]}".trim());
     }
 
     #[test]
@@ -137,7 +213,7 @@ mod tests {
         let json = JSON{height: 7, xdim: 2};
         let generated = json.generate(&code39.encode()[..]).unwrap();
 
-        assert_eq!(generated, "{\"height\":7,\"xdim\":2,\"encoding\":[1,0,0,1,0,1,1,0,1,1,0,1,0,1,1,0,1,0,0,1,0,1,0,1,1,0,1,0,1,1,0,0,1,0,1,0,1,1,0,1,1,0,1,1,0,0,1,0,1,0,1,0,1,0,1,0,0,1,1,0,1,0,1,1,0,1,0,0,1,0,1,1,0,1,1,0,1]}".trim());
+        assert_eq!(generated, "{\"schema_version\":2,\"dimensions\":\"1d\",\"height\":7,\"xdim\":2,\"encoding\":[1,0,0,1,0,1,1,0,1,1,0,1,0,1,1,0,1,0,0,1,0,1,0,1,1,0,1,0,1,1,0,0,1,0,1,0,1,1,0,1,1,0,1,1,0,0,1,0,1,0,1,0,1,0,1,0,0,1,1,0,1,0,1,1,0,1,0,0,1,0,1,1,0,1,1,0,1]}".trim());
     }
 
     #[test]
@@ -146,7 +222,7 @@ mod tests {
         let json = JSON::new();
         let generated = json.generate(&codabar.encode()[..]).unwrap();
 
-        assert_eq!(generated, "{\"height\":10,\"xdim\":1,\"encoding\":[1,0,1,1,0,0,1,0,0,1,0,1,1,0,1,0,0,1,0,1,0,1,0,0,1,1,0,1,0,1,0,1,0,1,0,0,1,0,0,1,1]}".trim());
+        assert_eq!(generated, "{\"schema_version\":2,\"dimensions\":\"1d\",\"height\":10,\"xdim\":1,\"encoding\":[1,0,1,1,0,0,1,0,0,1,0,1,1,0,1,0,0,1,0,1,0,1,0,0,1,1,0,1,0,1,0,1,0,1,0,0,1,0,0,1,1]}".trim());
     }
 
     #[test]
@@ -155,7 +231,7 @@ mod tests {
         let json = JSON{height: 7, xdim: 2};
         let generated = json.generate(&codabar.encode()[..]).unwrap();
 
-        assert_eq!(generated, "{\"height\":7,\"xdim\":2,\"encoding\":[1,0,1,1,0,0,1,0,0,1,0,1,0,1,1,0,1,0,0,1,0,1,0,1,0,1,0,0,1,1,0,1,0,1,0,1,1,0,0,1,0,1,1,0,1,0,1,0,0,1,0,1,0,0,1,0,1,0,1,1,0,1,0,1,0,0,1,0,0,1,1]}".trim());
+        assert_eq!(generated, "{\"schema_version\":2,\"dimensions\":\"1d\",\"height\":7,\"xdim\":2,\"encoding\":[1,0,1,1,0,0,1,0,0,1,0,1,0,1,1,0,1,0,0,1,0,1,0,1,0,1,0,0,1,1,0,1,0,1,0,1,1,0,0,1,0,1,1,0,1,0,1,0,0,1,0,1,0,0,1,0,1,0,1,1,0,1,0,1,0,0,1,0,0,1,1]}".trim());
     }
 
     #[test]
@@ -164,7 +240,7 @@ mod tests {
         let json = JSON::new();
         let generated = json.generate(&code128.encode()[..]).unwrap();
 
-        assert_eq!(generated, "{\"height\":10,\"xdim\":1,\"encoding\":[1,1,0,1,0,0,0,0,1,0,0,1,1,0,0,0,1,0,1,0,0,0,1,0,0,0,1,1,0,1,0,0,0,1,0,0,0,1,1,0,1,1,1,0,1,0,0,0,1,1,0,1,1,1,0,1,0,0,0,1,1,1,0,1,1,0,1,1,0,1,0,0,0,1,0,0,0,1,1,0,0,0,1,1,1,0,1,0,1,1]}".trim());
+        assert_eq!(generated, "{\"schema_version\":2,\"dimensions\":\"1d\",\"height\":10,\"xdim\":1,\"encoding\":[1,1,0,1,0,0,0,0,1,0,0,1,1,0,0,0,1,0,1,0,0,0,1,0,0,0,1,1,0,1,0,0,0,1,0,0,0,1,1,0,1,1,1,0,1,0,0,0,1,1,0,1,1,1,0,1,0,0,0,1,1,1,0,1,1,0,1,1,0,1,0,0,0,1,0,0,0,1,1,0,0,0,1,1,1,0,1,0,1,1]}".trim());
     }
 
     #[test]
@@ -173,7 +249,7 @@ mod tests {
         let json = JSON{height: 7, xdim: 2};
         let generated = json.generate(&code128.encode()[..]).unwrap();
 
-        assert_eq!(generated, "{\"height\":7,\"xdim\":2,\"encoding\":[1,1,0,1,0,0,0,0,1,0,0,1,1,0,0,0,1,0,1,0,0,0,1,0,0,0,1,1,0,1,0,0,0,1,0,0,0,1,1,0,1,1,1,0,1,0,0,0,1,1,0,1,1,1,0,1,0,0,0,1,1,1,0,1,1,0,1,1,0,1,0,0,0,1,0,0,0,1,1,0,0,0,1,1,1,0,1,0,1,1]}".trim());
+        assert_eq!(generated, "{\"schema_version\":2,\"dimensions\":\"1d\",\"height\":7,\"xdim\":2,\"encoding\":[1,1,0,1,0,0,0,0,1,0,0,1,1,0,0,0,1,0,1,0,0,0,1,0,0,0,1,1,0,1,0,0,0,1,0,0,0,1,1,0,1,1,1,0,1,0,0,0,1,1,0,1,1,1,0,1,0,0,0,1,1,1,0,1,1,0,1,1,0,1,0,0,0,1,0,0,0,1,1,0,0,0,1,1,1,0,1,0,1,1]}".trim());
     }
 
     #[test]
@@ -182,7 +258,7 @@ mod tests {
         let json = JSON::new();
         let generated = json.generate(&ean2.encode()[..]).unwrap();
 
-        assert_eq!(generated, "{\"height\":10,\"xdim\":1,\"encoding\":[1,0,1,1,0,1,0,0,0,0,1,0,1,0,1,0,0,0,1,1]}".trim());
+        assert_eq!(generated, "{\"schema_version\":2,\"dimensions\":\"1d\",\"height\":10,\"xdim\":1,\"encoding\":[1,0,1,1,0,1,0,0,0,0,1,0,1,0,1,0,0,0,1,1]}".trim());
     }
 
     #[test]
@@ -191,7 +267,7 @@ mod tests {
         let json = JSON::new();
         let generated = json.generate(&ean5.encode()[..]).unwrap();
 
-        assert_eq!(generated, "{\"height\":10,\"xdim\":1,\"encoding\":[1,0,1,1,0,1,1,0,0,0,1,0,1,0,1,0,0,1,1,1,0,1,0,0,1,0,0,0,1,0,1,0,0,0,1,0,1,1,0,1,0,0,0,1,0,1,1]}".trim());
+        assert_eq!(generated, "{\"schema_version\":2,\"dimensions\":\"1d\",\"height\":10,\"xdim\":1,\"encoding\":[1,0,1,1,0,1,1,0,0,0,1,0,1,0,1,0,0,1,1,1,0,1,0,0,1,0,0,0,1,0,1,0,0,0,1,0,1,1,0,1,0,0,0,1,0,1,1]}".trim());
     }
 
     #[test]
@@ -200,7 +276,7 @@ mod tests {
         let json = JSON::new();
         let generated = json.generate(&itf.encode()[..]).unwrap();
 
-        assert_eq!(generated, "{\"height\":10,\"xdim\":1,\"encoding\":[1,0,1,0,1,1,1,0,1,0,0,0,1,0,1,0,1,1,1,0,0,0,1,1,1,0,1,1,1,0,1,0,0,0,1,0,1,0,0,0,1,1,1,0,1,0,1,1,1,0,1,0,0,0,1,0,0,0,1,1,0,1]}".trim());
+        assert_eq!(generated, "{\"schema_version\":2,\"dimensions\":\"1d\",\"height\":10,\"xdim\":1,\"encoding\":[1,0,1,0,1,1,1,0,1,0,0,0,1,0,1,0,1,1,1,0,0,0,1,1,1,0,1,1,1,0,1,0,0,0,1,0,1,0,0,0,1,1,1,0,1,0,1,1,1,0,1,0,0,0,1,0,0,0,1,1,0,1]}".trim());
     }
 
     #[test]
@@ -209,6 +285,6 @@ mod tests {
         let json = JSON::new();
         let generated = json.generate(&code11.encode()[..]).unwrap();
 
-        assert_eq!(generated, "{\"height\":10,\"xdim\":1,\"encoding\":[1,0,1,1,0,0,1,0,1,1,0,1,0,1,1,0,1,1,0,1,0,1,1,0,1,1,0,1,0,1,1,0,1,0,1,1,0,1,0,1,1,0,1,0,1,0,1,1,0,1,0,1,0,1,1,0,1,0,1,0,1,0,1,1,0,1,0,1,1,0,1,0,0,1,0,1,0,1,0,1,1,0,1,0,1,1,0,0,1]}".trim());
+        assert_eq!(generated, "{\"schema_version\":2,\"dimensions\":\"1d\",\"height\":10,\"xdim\":1,\"encoding\":[1,0,1,1,0,0,1,0,1,1,0,1,0,1,1,0,1,1,0,1,0,1,1,0,1,1,0,1,0,1,1,0,1,0,1,1,0,1,0,1,1,0,1,0,1,0,1,1,0,1,0,1,0,1,1,0,1,0,1,0,1,0,1,1,0,1,0,1,1,0,1,0,0,1,0,1,0,1,0,1,1,0,1,0,1,1,0,0,1]}".trim());
     }
 }
@@ -10,6 +10,8 @@
 //! barcoders = {version = "*", features = ["image"]}
 //! ```
 
+pub mod visitor;
+
 #[cfg(feature = "ascii")]
 pub mod ascii;
 
@@ -21,3 +23,6 @@ pub mod image;
 
 #[cfg(feature = "svg")]
 pub mod svg;
+
+#[cfg(feature = "tiny-skia")]
+pub mod pixmap;
@@ -0,0 +1,232 @@
+//! Functionality for drawing barcodes into an existing `tiny_skia::Pixmap`.
+//!
+//! Rather than producing a standalone image, the barcode is drawn directly onto a canvas that
+//! may already hold the rest of a scene, which avoids a compositing round-trip.
+//!
+//! For example:
+//!
+//! ```rust
+//! extern crate tiny_skia;
+//! # extern crate barcoders;
+//!
+//! use barcoders::generators::pixmap::*;
+//! use barcoders::sym::ean8::EAN8;
+//! use tiny_skia::Pixmap;
+//!
+//! # fn main() {
+//! let mut pixmap = Pixmap::new(100, 50).unwrap();
+//! let ean8 = EAN8::new("1234567").unwrap();
+//!
+//! draw_to_pixmap(&ean8.encode(), &mut pixmap, 10.0, 5.0, &DrawOptions::new(40.0)).unwrap();
+//! # }
+//! ```
+
+use tiny_skia::{Paint, Pixmap, Rect, Transform};
+use error::{Error, Result};
+use sym::helpers;
+
+/// Represents a RGBA color for the barcode foreground and background.
+#[derive(Copy, Clone, Debug)]
+pub struct Color {
+    /// Reg, Green, Blue, Alpha value.
+    pub rgba: [u8; 4],
+}
+
+impl Color {
+    /// Constructor.
+    pub fn new(rgba: [u8; 4]) -> Color {
+        Color{rgba}
+    }
+
+    /// Constructor for black (#000000).
+    pub fn black() -> Color {
+        Color::new([0, 0, 0, 255])
+    }
+
+    /// Constructor for white (#FFFFFF).
+    pub fn white() -> Color {
+        Color::new([255, 255, 255, 255])
+    }
+
+    fn to_paint(self, anti_alias: bool) -> Paint<'static> {
+        let mut paint = Paint::default();
+        let [r, g, b, a] = self.rgba;
+
+        paint.set_color_rgba8(r, g, b, a);
+        paint.anti_alias = anti_alias;
+        paint
+    }
+}
+
+/// Options for drawing a barcode into a pixmap.
+#[derive(Copy, Clone, Debug)]
+pub struct DrawOptions {
+    /// The height of the barcode in pixels.
+    pub height: f32,
+    /// The X dimension. Specifies the width of the "narrow" bars, which may be fractional.
+    pub xdim: f32,
+    /// The RGBA color for the foreground.
+    pub foreground: Color,
+    /// The RGBA color for the background. A fully transparent background leaves the existing
+    /// pixels between the bars untouched.
+    pub background: Color,
+    /// Whether to anti-alias the edges of the bars.
+    pub anti_alias: bool,
+    /// Whether to round the edges of the bars to whole pixels, so that they stay crisp with a
+    /// fractional X dimension. Each module boundary is rounded to the nearest pixel (so the total
+    /// width is the rounded width of the barcode). Drawing fails if a bar or space would end up
+    /// narrower than a pixel.
+    pub snap_to_pixel: bool,
+}
+
+impl DrawOptions {
+    /// Returns new options with default values.
+    pub fn new(height: f32) -> DrawOptions {
+        DrawOptions {
+            height,
+            xdim: 1.0,
+            foreground: Color::black(),
+            background: Color::white(),
+            anti_alias: true,
+            snap_to_pixel: false,
+        }
+    }
+}
+
+/// Draws the given barcode into the pixmap, with its top-left corner at (`x`, `y`).
+/// Returns a `Result<(), Error>`, failing if the barcode would have an invalid size.
+pub fn draw_to_pixmap(barcode: &[u8], pixmap: &mut Pixmap, x: f32, y: f32, opts: &DrawOptions) -> Result<()> {
+    let edge = |modules: u32| match opts.snap_to_pixel {
+        true => (x + modules as f32 * opts.xdim).round(),
+        false => x + modules as f32 * opts.xdim,
+    };
+    let bar_rect = |left: f32, right: f32| Rect::from_xywh(left, y, right - left, opts.height).ok_or(Error::Generate);
+
+    if barcode.is_empty() {
+        return Ok(());
+    }
+
+    if opts.background.rgba[3] > 0 {
+        let rect = bar_rect(edge(0), edge(barcode.len() as u32))?;
+        pixmap.fill_rect(rect, &opts.background.to_paint(opts.anti_alias), Transform::identity(), None);
+    }
+
+    let paint = opts.foreground.to_paint(opts.anti_alias);
+    let mut rects = vec![];
+    let mut start = 0;
+
+    // Adjacent modules are drawn as a single bar, so that anti-aliasing doesn't produce seams.
+    // Each bar spans the module boundaries on either side of it, which are snapped (rather than
+    // the bar's own edges), so that neighbouring bars can't overlap.
+    for (dark, len) in helpers::modules_to_rle(barcode) {
+        let (left, right) = (edge(start), edge(start + len));
+
+        // A bar or space that snaps to nothing would merge its neighbours.
+        if opts.snap_to_pixel && right - left < 1.0 {
+            return Err(Error::Generate);
+        }
+
+        if dark {
+            rects.push(bar_rect(left, right)?);
+        }
+
+        start += len;
+    }
+
+    for rect in rects {
+        pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sym::ean8::*;
+    use generators::pixmap::*;
+    use error::Error;
+    use tiny_skia::Pixmap;
+
+    fn rgba(pixmap: &Pixmap, x: u32, y: u32) -> [u8; 4] {
+        let p = pixmap.pixel(x, y).unwrap();
+
+        [p.red(), p.green(), p.blue(), p.alpha()]
+    }
+
+    #[test]
+    fn ean_8_to_pixmap() {
+        let ean8 = EAN8::new("1234567").unwrap();
+        let mut pixmap = Pixmap::new(160, 50).unwrap();
+        let opts = DrawOptions{xdim: 2.0, foreground: Color::new([255, 0, 0, 255]), ..DrawOptions::new(20.0)};
+
+        draw_to_pixmap(&ean8.encode(), &mut pixmap, 10.0, 5.0, &opts).unwrap();
+
+        // The first guard bar, followed by a space.
+        assert_eq!(rgba(&pixmap, 10, 5), [255, 0, 0, 255]);
+        assert_eq!(rgba(&pixmap, 11, 24), [255, 0, 0, 255]);
+        assert_eq!(rgba(&pixmap, 12, 10), [255, 255, 255, 255]);
+        // Outside of the barcode, the pixmap is untouched.
+        assert_eq!(rgba(&pixmap, 9, 10), [0, 0, 0, 0]);
+        assert_eq!(rgba(&pixmap, 10, 25), [0, 0, 0, 0]);
+        assert_eq!(rgba(&pixmap, 144, 10), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn snapped_ean_8_to_pixmap() {
+        let ean8 = EAN8::new("1234567").unwrap();
+        let mut pixmap = Pixmap::new(120, 10).unwrap();
+        let opts = DrawOptions{xdim: 1.4, snap_to_pixel: true, ..DrawOptions::new(10.0)};
+        let encoded = ean8.encode();
+
+        draw_to_pixmap(&encoded, &mut pixmap, 10.3, 0.0, &opts).unwrap();
+
+        let row: Vec<[u8; 4]> = (0..120).map(|x| rgba(&pixmap, x, 5)).collect();
+        let dark: Vec<usize> = (0..120).filter(|&x| row[x] == [0, 0, 0, 255]).collect();
+        let bars = dark.iter().filter(|&&x| row[x - 1] != [0, 0, 0, 255]).count();
+
+        // Every pixel is either a whole bar or a whole space, with no blurred edges.
+        assert!(row[10..104].iter().all(|&p| p == [0, 0, 0, 255] || p == [255, 255, 255, 255]));
+        assert_eq!(bars, encoded.windows(2).filter(|w| w == &[0, 1]).count() + 1);
+        // The edges at 10.3 and 10.3 + (67 * 1.4) round to 10 and 104.
+        assert_eq!(dark.first(), Some(&10));
+        assert_eq!(dark.last(), Some(&103));
+        assert_eq!(row[104], [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn snapped_narrow_bars_to_pixmap() {
+        let barcode = [1, 0, 0, 0, 1, 0, 0, 0, 1];
+        let mut pixmap = Pixmap::new(10, 10).unwrap();
+        let opts = DrawOptions{xdim: 0.75, snap_to_pixel: true, ..DrawOptions::new(10.0)};
+
+        draw_to_pixmap(&barcode, &mut pixmap, 0.0, 0.0, &opts).unwrap();
+
+        // Every bar and space survives snapping, without neighbouring bars overlapping.
+        let row: Vec<bool> = (0..10).map(|x| rgba(&pixmap, x, 5) == [0, 0, 0, 255]).collect();
+        let runs: Vec<bool> = row[..7].iter().enumerate()
+            .filter(|&(i, &dark)| i == 0 || row[i - 1] != dark)
+            .map(|(_, &dark)| dark)
+            .collect();
+
+        assert_eq!(runs, vec![true, false, true, false, true]);
+        assert_eq!(rgba(&pixmap, 7, 5), [0, 0, 0, 0]);
+
+        // At 0.4 pixels per module, a single-module bar would vanish.
+        let opts = DrawOptions{xdim: 0.4, ..opts};
+
+        assert_eq!(draw_to_pixmap(&barcode, &mut pixmap, 0.0, 0.0, &opts).err(), Some(Error::Generate));
+    }
+
+    #[test]
+    fn transparent_background_to_pixmap() {
+        let ean8 = EAN8::new("1234567").unwrap();
+        let mut pixmap = Pixmap::new(80, 20).unwrap();
+        let opts = DrawOptions{background: Color::new([255, 255, 255, 0]), ..DrawOptions::new(20.0)};
+
+        draw_to_pixmap(&ean8.encode(), &mut pixmap, 0.0, 0.0, &opts).unwrap();
+
+        assert_eq!(rgba(&pixmap, 0, 0), [0, 0, 0, 255]);
+        assert_eq!(rgba(&pixmap, 1, 0), [0, 0, 0, 0]);
+        assert_eq!(draw_to_pixmap(&[1], &mut pixmap, 0.0, 0.0, &DrawOptions::new(-1.0)).err(), Some(Error::Generate));
+    }
+}
@@ -12,13 +12,25 @@
 //! let svg = SVG{height: 80,
 //!               xdim: 1,
 //!               background: Color{rgba: [255, 0, 0, 255]},
-//!               foreground: Color::black()};
+//!               foreground: Color::black(),
+//!               render_mode: RenderMode::Fill,
+//!               bar_width_reduction: 0.0,
+//!               invert: false,
+//!               left_quiet_zone: 0,
+//!               right_quiet_zone: 0};
 //!
 //! // Or use the constructor for defaults (you must specify the height).
 //! let svg = SVG::new(100);
 //! ```
+//!
+//! The height is always the one given. To use the recommended minimum height for a barcode
+//! instead, call `with_min_height`.
 
-use error::Result;
+use error::{Error, Result};
+use sym::{Barcode, helpers};
+use sym::postal::BarState;
+use generators::visitor::{self, RenderOptions};
+use std::ops::Range;
 
 trait ToHex {
     fn to_hex(&self) -> String;
@@ -48,7 +60,7 @@ pub struct Color {
 impl Color {
     /// Constructor.
     pub fn new(rgba: [u8; 4]) -> Color {
-        Color{rgba: rgba}
+        Color{rgba}
     }
 
     /// Constructor for black (#000000).
@@ -61,7 +73,7 @@ impl Color {
         Color::new([255, 255, 255, 255])
     }
 
-    fn to_opacity(&self) -> String {
+    fn to_opacity(self) -> String {
         format!("{:.*}", 2, (self.rgba[3] as f64 / 255.0))
     }
 }
@@ -76,6 +88,110 @@ impl ToHex for Color {
     }
 }
 
+// Escapes the characters that may not appear literally within SVG text.
+fn escape_xml(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            '"' => "&quot;".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Specifies how the bars of a barcode are drawn.
+#[derive(Copy, Clone, Debug)]
+pub enum RenderMode {
+    /// Each bar is drawn as a filled rectangle.
+    Fill,
+    /// Each bar is drawn as an unfilled rectangle outlined with a stroke, centered on the bar's
+    /// edges. Adjacent modules are outlined as a single bar.
+    Stroke {
+        /// The stroke width, in pixels.
+        width: u32,
+        /// The RGBA color for the stroke.
+        color: Color,
+    },
+}
+
+// Formats a pixel value with at most three decimal places, dropping any trailing zeros.
+fn format_px(value: f64) -> String {
+    let formatted = format!("{:.3}", value);
+
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+// Formats a piece of text centered on the given x position, with its baseline at y.
+fn label(x: f64, y: u32, options: &TextOptions, text: &str) -> String {
+    let opacity = match &options.color.to_opacity()[..] {
+        "1.00" | "1" => "".to_string(),
+        o => format!(" fill-opacity=\"{}\"", o),
+    };
+
+    format!("<text x=\"{}\" y=\"{}\" font-family=\"monospace\" font-size=\"{}\" text-anchor=\"middle\" fill=\"#{}\"{}>{}</text>",
+            format_px(x), y, options.size, options.color.to_hex(), opacity, escape_xml(text))
+}
+
+/// Specifies where human-readable text is placed relative to the bars.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TextPosition {
+    /// The text is drawn beneath the bars. This is the default.
+    Below,
+    /// The text is drawn above the bars.
+    Above,
+    /// No text is drawn.
+    None,
+}
+
+/// Options for the human-readable text drawn by `SVG::generate_with_text`.
+#[derive(Copy, Clone, Debug)]
+pub struct TextOptions {
+    /// Where the text is placed.
+    pub position: TextPosition,
+    /// The RGBA color for the text, independent of the bars.
+    pub color: Color,
+    /// The font size, in pixels.
+    pub size: u32,
+}
+
+impl Default for TextOptions {
+    fn default() -> TextOptions {
+        TextOptions {
+            position: TextPosition::Below,
+            color: Color::black(),
+            size: 10,
+        }
+    }
+}
+
+// The quiet zone shared by stacked barcodes, in modules.
+const STACK_QUIET_ZONE_MODULES: u32 = 10;
+
+/// A barcode defined once as an SVG `<symbol>`, which can then be placed many times within a
+/// larger document without repeating its bars. See `SVG::generate_symbol`.
+#[derive(Clone, Debug)]
+pub struct Symbol {
+    id: String,
+    width: u32,
+    height: u32,
+    bars: String,
+}
+
+impl Symbol {
+    /// Returns the `<defs>` element defining the symbol, to be included once in the document.
+    pub fn defs(&self) -> String {
+        format!("<defs><symbol id=\"{id}\" viewBox=\"0 0 {w} {h}\" width=\"{w}\" height=\"{h}\">{b}</symbol></defs>",
+                id=self.id, w=self.width, h=self.height, b=self.bars)
+    }
+
+    /// Returns a `<use>` element placing the symbol with its top-left corner at (`x`, `y`).
+    pub fn use_at(&self, x: u32, y: u32) -> String {
+        format!("<use href=\"#{}\" x=\"{}\" y=\"{}\"/>", self.id, x, y)
+    }
+}
+
 /// The SVG barcode generator type.
 #[derive(Copy, Clone, Debug)]
 pub struct SVG {
@@ -88,47 +204,363 @@ pub struct SVG {
     pub foreground: Color,
     /// The RGBA color for the foreground.
     pub background: Color,
+    /// Whether the bars are filled or outlined.
+    pub render_mode: RenderMode,
+    /// The number of pixels to shave off each bar (split evenly between its two edges) to
+    /// compensate for ink spread, at most half the bar. The module pitch is unchanged, so
+    /// spaces widen correspondingly.
+    pub bar_width_reduction: f32,
+    /// Whether to invert the barcode, drawing the spaces rather than the bars in the foreground
+    /// color. Anything outside of the barcode itself, such as a quiet zone, stays background.
+    pub invert: bool,
+    /// The number of blank modules to leave before the barcode, in the background color.
+    /// Applies when generating a single barcode, with or without text.
+    pub left_quiet_zone: u32,
+    /// The number of blank modules to leave after the barcode, as with `left_quiet_zone`.
+    pub right_quiet_zone: u32,
 }
 
 impl SVG {
     /// Returns a new SVG with default values.
     pub fn new(height: u32) -> SVG {
         SVG {
-            height: height,
+            height,
             xdim: 1,
             foreground: Color{rgba: [0, 0, 0, 255]},
             background: Color{rgba: [255, 255, 255, 255]},
+            render_mode: RenderMode::Fill,
+            bar_width_reduction: 0.0,
+            invert: false,
+            left_quiet_zone: 0,
+            right_quiet_zone: 0,
         }
     }
 
-    fn rect(&self, style: u8, offset: u32, width: u32) -> String {
-        let fill = match style {
-            1 => self.foreground,
-            _ => self.background,
-        };
+    /// Returns a copy of this SVG with its height set to the recommended minimum height for
+    /// the given barcode at the current X dimension.
+    ///
+    /// This is opt-in: `SVG::new` always takes an explicit height, which is never replaced by
+    /// the recommendation unless this method is called.
+    pub fn with_min_height<B: Barcode + ?Sized>(self, barcode: &B) -> SVG {
+        let width = (barcode.encode().len() as u32) * self.xdim;
+
+        SVG {
+            height: barcode.min_height(width),
+            ..self
+        }
+    }
+
+    /// Returns a copy of this SVG with both quiet zones set to the symmetric value recommended
+    /// for the given barcode (see `Barcode::metadata`).
+    pub fn with_quiet_zone<B: Barcode + ?Sized>(self, barcode: &B) -> SVG {
+        let modules = barcode.metadata().quiet_zone_modules as u32;
+
+        SVG {
+            left_quiet_zone: modules,
+            right_quiet_zone: modules,
+            ..self
+        }
+    }
+
+    /// Returns a copy of this SVG with the largest X dimension at which the given barcode fits
+    /// within `width_mm` millimeters when printed at `dpi` pixels per inch, including the quiet
+    /// zones. The X dimension is never less than 1 pixel.
+    pub fn with_physical_width<B: Barcode + ?Sized>(self, barcode: &B, width_mm: f64, dpi: u32) -> SVG {
+        SVG {
+            xdim: helpers::xdim_from_physical(self.quiet_modules(barcode), width_mm, dpi),
+            ..self
+        }
+    }
+
+    /// Returns the printed width of the given barcode in millimeters, at `dpi` pixels per inch,
+    /// including the quiet zones.
+    pub fn total_width_mm<B: Barcode + ?Sized>(&self, barcode: &B, dpi: u32) -> f64 {
+        helpers::width_mm(self.quiet_modules(barcode), self.xdim, dpi)
+    }
+
+    // Returns the number of modules in the given barcode along with its quiet zones.
+    fn quiet_modules<B: Barcode + ?Sized>(&self, barcode: &B) -> usize {
+        barcode.encode().len() + (self.left_quiet_zone + self.right_quiet_zone) as usize
+    }
+
+    // Returns the width of the given barcode along with its quiet zones, in pixels.
+    fn quiet_width(&self, barcode: &[u8]) -> u32 {
+        (barcode.len() as u32 + self.left_quiet_zone + self.right_quiet_zone) * self.xdim
+    }
+
+    fn rect(&self, fill: Color, offset: f64, width: f64) -> String {
+        self.rect_at(fill, offset, 0.0, width, f64::from(self.height))
+    }
 
+    fn rect_at(&self, fill: Color, x: f64, y: f64, width: f64, height: f64) -> String {
         let opacity = match &fill.to_opacity()[..] {
             "1.00" | "1" => "".to_string(),
             o => format!(" fill-opacity=\"{}\" ", o),
         };
 
-        format!("<rect x=\"{}\" y=\"0\" width=\"{}\" height=\"{}\" fill=\"#{}\"{}/>",
-                offset, width, self.height, fill.to_hex(), opacity)
+        format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#{}\"{}/>",
+                format_px(x), format_px(y), format_px(width), format_px(height), fill.to_hex(), opacity)
+    }
+
+    fn outline(&self, stroke: Color, stroke_width: u32, offset: f64, width: f64) -> String {
+        let opacity = match &stroke.to_opacity()[..] {
+            "1.00" | "1" => "".to_string(),
+            o => format!(" stroke-opacity=\"{}\" ", o),
+        };
+
+        format!("<rect x=\"{}\" y=\"0\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"#{}\" stroke-width=\"{}\"{}/>",
+                format_px(offset), format_px(width), self.height, stroke.to_hex(), stroke_width, opacity)
     }
 
     /// Generates the given barcode. Returns a `Result<String, Error>` of the SVG data or an
     /// error message.
     pub fn generate<T: AsRef<[u8]>>(&self, barcode: T) -> Result<String> {
+        self.generate_with_colors(barcode, &[])
+    }
+
+    /// Generates the given barcode, drawing the bars within each range of module indices in the
+    /// given color rather than the foreground color. Where ranges overlap, the last one wins.
+    /// Returns a `Result<String, Error>` of the SVG data or an error message.
+    pub fn generate_with_colors<T: AsRef<[u8]>>(&self, barcode: T, colors: &[(Range<usize>, Color)]) -> Result<String> {
+        let barcode = barcode.as_ref();
+        let width = self.quiet_width(barcode);
+        let bars = match self.left_quiet_zone {
+            0 => self.bar_rects(barcode, colors),
+            n => format!("<g transform=\"translate({} 0)\">{}</g>", n * self.xdim, self.bar_rects(barcode, colors)),
+        };
+
+        Ok(format!("<svg version=\"1.1\" viewBox=\"0 0 {w} {h}\">{s}{r}</svg>",
+                   w=width, h=self.height, s=self.rect(self.background, 0.0, f64::from(width)), r=bars))
+    }
+
+    /// Generates the given barcode as a reusable `Symbol` with the given id, rather than a
+    /// standalone SVG.
+    /// Returns a `Result<Symbol, Error>`, failing if the id is empty.
+    pub fn generate_symbol<T: AsRef<[u8]>>(&self, barcode: T, id: &str) -> Result<Symbol> {
         let barcode = barcode.as_ref();
         let width = (barcode.len() as u32) * self.xdim;
-        let rects: String = barcode.iter()
-            .enumerate()
-            .filter(|&(_, &n)| n == 1)
-            .map(|(i, &n)| self.rect(n, i as u32 * self.xdim, self.xdim))
+
+        if id.is_empty() {
+            return Err(Error::Generate);
+        }
+
+        Ok(Symbol {
+            id: escape_xml(id),
+            width,
+            height: self.height,
+            bars: format!("{}{}", self.rect(self.background, 0.0, f64::from(width)), self.bar_rects(barcode, &[])),
+        })
+    }
+
+    /// Generates the given barcode along with human-readable text. Each piece of text is
+    /// centered on the given range of module indices, which allows for layouts such as EAN-13's
+    /// digits split between the guards. The image grows to make room for the text.
+    /// Returns a `Result<String, Error>` of the SVG data or an error message.
+    pub fn generate_with_text<T: AsRef<[u8]>>(&self, barcode: T, text: &[(Range<usize>, &str)], options: TextOptions) -> Result<String> {
+        self.generate_labelled(barcode.as_ref(), text, options, &[])
+    }
+
+    /// Generates the given barcode directly from the symbology, using its default
+    /// human-readable text (beneath the bars) and extending its guard bars down alongside the
+    /// text, as with EAN-13.
+    /// Returns a `Result<String, Error>` of the SVG data or an error message.
+    pub fn generate_from<B: Barcode + ?Sized>(&self, barcode: &B) -> Result<String> {
+        let text = barcode.human_readable();
+        let guards = barcode.guard_ranges();
+
+        if text.is_empty() && guards.is_empty() {
+            return self.generate(barcode.encode());
+        }
+
+        let text: Vec<(Range<usize>, &str)> = text.iter().map(|(r, t)| (r.clone(), &t[..])).collect();
+
+        self.generate_labelled(&barcode.encode(), &text, TextOptions::default(), &guards)
+    }
+
+    fn generate_labelled(&self, barcode: &[u8], text: &[(Range<usize>, &str)], options: TextOptions, guards: &[Range<usize>]) -> Result<String> {
+        let width = self.quiet_width(barcode);
+        let left = self.left_quiet_zone * self.xdim;
+        let gap = options.size / 4;
+        let (bars_y, text_y) = match options.position {
+            TextPosition::None => return self.generate(barcode),
+            TextPosition::Above => (options.size + gap, options.size),
+            TextPosition::Below => (0, self.height + gap + options.size),
+        };
+        // Guards are extended by half the text height, stopping short of the digits.
+        let guard_bars = match options.position {
+            TextPosition::Below if !guards.is_empty() => {
+                let modules: Vec<u8> = barcode.iter()
+                    .enumerate()
+                    .map(|(i, &m)| if guards.iter().any(|r| r.contains(&i)) { m ^ (self.invert as u8) } else { 0 })
+                    .collect();
+                let extension = SVG{height: gap + (options.size / 2), invert: false, ..*self};
+
+                format!("<g transform=\"translate({} {})\">{}</g>", left, self.height, extension.bar_rects(&modules, &[]))
+            }
+            _ => "".to_string(),
+        };
+        let labels: String = text.iter()
+            .map(|&(ref r, t)| label(f64::from(left) + ((r.start + r.end) as f64 * f64::from(self.xdim) / 2.0), text_y, &options, t))
             .collect();
+        let height = self.height + gap + options.size;
+        let sheet = SVG{height, ..*self};
+
+        Ok(format!("<svg version=\"1.1\" viewBox=\"0 0 {w} {h}\">{s}<g transform=\"translate({x} {y})\">{r}</g>{g}{t}</svg>",
+                   w=width, h=height, s=sheet.rect(self.background, 0.0, f64::from(width)),
+                   x=left, y=bars_y, r=self.bar_rects(barcode, &[]), g=guard_bars, t=labels))
+    }
+
+    /// Generates the given 4-state barcode (see `sym::postal`), with each bar one X dimension
+    /// wide and separated by a space of the same width. The render mode and inversion are
+    /// ignored.
+    /// Returns a `Result<String, Error>` of the SVG data or an error message.
+    pub fn generate_four_state(&self, bars: &[BarState]) -> Result<String> {
+        let width = (bars.len() as u32 * 2).saturating_sub(1) * self.xdim;
+        let opts = RenderOptions{xdim: f64::from(self.xdim),
+                                 bar_width_reduction: f64::from(self.bar_width_reduction),
+                                 ..RenderOptions::new(f64::from(self.height))};
+        let mut rects = String::new();
+
+        visitor::render_four_state(bars, &opts, |bar| {
+            rects.push_str(&self.rect_at(self.foreground, bar.x, bar.y, bar.width, bar.height))
+        });
 
         Ok(format!("<svg version=\"1.1\" viewBox=\"0 0 {w} {h}\">{s}{r}</svg>",
-                   w=width, h=self.height, s=self.rect(0, 0, width), r=rects))
+                   w=width, h=self.height, s=self.rect(self.background, 0.0, f64::from(width)), r=rects))
+    }
+
+    /// Lays out the given barcodes in a grid with the given number of columns, returning a
+    /// single SVG. Each cell is as wide as the widest barcode, and `spacing` pixels separate
+    /// the cells from each other and from the edges of the sheet.
+    /// Returns a `Result<String, Error>` of the SVG data or an error message.
+    pub fn tile<T: AsRef<[u8]>>(&self, barcodes: &[T], columns: usize, spacing: u32) -> Result<String> {
+        if columns == 0 {
+            return Err(Error::Generate);
+        }
+
+        let cell_width = barcodes.iter()
+                                 .map(|b| b.as_ref().len() as u32 * self.xdim)
+                                 .max()
+                                 .unwrap_or(0);
+        let rows = ((barcodes.len() + columns - 1) / columns) as u32;
+        let columns = columns as u32;
+        let width = (columns * cell_width) + ((columns + 1) * spacing);
+        let height = (rows * self.height) + ((rows + 1) * spacing);
+        let cells: String = barcodes.iter()
+            .enumerate()
+            .map(|(i, b)| {
+                let x = spacing + ((i as u32 % columns) * (cell_width + spacing));
+                let y = spacing + ((i as u32 / columns) * (self.height + spacing));
+
+                format!("<g transform=\"translate({} {})\">{}</g>", x, y, self.bar_rects(b.as_ref(), &[]))
+            })
+            .collect();
+
+        let sheet = SVG{height, ..*self};
+
+        Ok(format!("<svg version=\"1.1\" viewBox=\"0 0 {w} {h}\">{s}{c}</svg>",
+                   w=width, h=height, s=sheet.rect(self.background, 0.0, f64::from(width)), c=cells))
+    }
+
+    /// Stacks the given barcodes vertically in a single column, each centered horizontally and
+    /// followed by its optional caption (drawn as with `TextOptions::default`). `spacing` pixels
+    /// separate the barcodes from each other and from the top and bottom of the sheet, while a
+    /// shared quiet zone of 10 modules is left on either side of the widest barcode.
+    /// Returns a `Result<String, Error>` of the SVG data or an error message.
+    pub fn stack(&self, barcodes: &[(Vec<u8>, Option<String>)], spacing: u32) -> Result<String> {
+        let options = TextOptions::default();
+        let gap = options.size / 4;
+        let quiet_zone = STACK_QUIET_ZONE_MODULES * self.xdim;
+        let content_width = barcodes.iter()
+                                    .map(|b| b.0.len() as u32 * self.xdim)
+                                    .max()
+                                    .unwrap_or(0);
+        let width = content_width + (2 * quiet_zone);
+        let mut y = spacing;
+        let mut groups = String::new();
+
+        for (barcode, caption) in barcodes {
+            let barcode_width = barcode.len() as u32 * self.xdim;
+            let x = f64::from(quiet_zone) + (f64::from(content_width - barcode_width) / 2.0);
+            let text = match caption {
+                Some(t) => label(f64::from(barcode_width) / 2.0, self.height + gap + options.size, &options, t),
+                None => "".to_string(),
+            };
+
+            groups.push_str(&format!("<g transform=\"translate({} {})\">{}{}</g>",
+                                     format_px(x), y, self.bar_rects(barcode, &[]), text));
+
+            y += self.height + spacing + if caption.is_some() { gap + options.size } else { 0 };
+        }
+
+        let sheet = SVG{height: y, ..*self};
+
+        Ok(format!("<svg version=\"1.1\" viewBox=\"0 0 {w} {h}\">{s}{g}</svg>",
+                   w=width, h=y, s=sheet.rect(self.background, 0.0, f64::from(width)), g=groups))
+    }
+
+    fn bar_rects(&self, barcode: &[u8], colors: &[(Range<usize>, Color)]) -> String {
+        let xdim = f64::from(self.xdim);
+        let inverted;
+        let barcode = if self.invert {
+            inverted = helpers::invert_modules(barcode);
+            &inverted[..]
+        } else {
+            barcode
+        };
+
+        match self.render_mode {
+            RenderMode::Fill if self.bar_width_reduction <= 0.0 => barcode.iter()
+                .enumerate()
+                .filter(|&(_, &n)| n == 1)
+                .map(|(i, _)| self.rect(self.module_color(i, self.foreground, colors), i as f64 * xdim, xdim))
+                .collect(),
+            RenderMode::Fill => self.bars(barcode, self.foreground, colors)
+                .iter()
+                .map(|&(start, len, c)| {
+                    let (offset, width) = self.reduce_bar(start, len);
+                    self.rect(c, offset, width)
+                })
+                .collect(),
+            RenderMode::Stroke{width: stroke_width, color} => self.bars(barcode, color, colors)
+                .iter()
+                .map(|&(start, len, c)| {
+                    let (offset, width) = self.reduce_bar(start, len);
+                    self.outline(c, stroke_width, offset, width)
+                })
+                .collect(),
+        }
+    }
+
+    // Returns the offset and width of a bar after applying the bar width reduction.
+    fn reduce_bar(&self, start: usize, len: usize) -> (f64, f64) {
+        let width = len as f64 * f64::from(self.xdim);
+        let shaved = f64::from(self.bar_width_reduction).max(0.0).min(width / 2.0);
+
+        ((start as f64 * f64::from(self.xdim)) + (shaved / 2.0), width - shaved)
+    }
+
+    fn module_color(&self, index: usize, default: Color, colors: &[(Range<usize>, Color)]) -> Color {
+        colors.iter()
+              .rev()
+              .find(|&(r, _)| r.contains(&index))
+              .map_or(default, |&(_, c)| c)
+    }
+
+    // Groups runs of adjacent, same-colored bar modules into (start, length, color) bars.
+    fn bars(&self, barcode: &[u8], default: Color, colors: &[(Range<usize>, Color)]) -> Vec<(usize, usize, Color)> {
+        let mut bars: Vec<(usize, usize, Color)> = vec![];
+
+        for (i, _) in barcode.iter().enumerate().filter(|&(_, &n)| n == 1) {
+            let color = self.module_color(i, default, colors);
+
+            match bars.last_mut() {
+                Some(&mut (start, ref mut len, c)) if start + *len == i && c.rgba == color.rgba => *len += 1,
+                _ => bars.push((i, 1, color)),
+            }
+        }
+
+        bars
     }
 }
 
@@ -143,6 +575,7 @@ mod tests {
     use ::sym::ean_supp::*;
     use ::sym::tf::*;
     use ::sym::codabar::*;
+    use ::sym::postal::*;
     use ::generators::svg::*;
     use std::io::prelude::*;
     use std::io::BufWriter;
@@ -155,11 +588,11 @@ mod tests {
     fn write_file(data: &str, file: &'static str) {
         let path = open_file(file);
         let mut writer = BufWriter::new(path);
-        writer.write(data.as_bytes()).unwrap();
+        writer.write_all(data.as_bytes()).unwrap();
     }
 
     fn open_file(name: &'static str) -> File {
-        File::create(&Path::new(&format!("{}/{}", TEST_DATA_BASE, name)[..])).unwrap()
+        File::create(Path::new(&format!("{}/{}", TEST_DATA_BASE, name)[..])).unwrap()
     }
 
     #[test]
@@ -179,7 +612,12 @@ mod tests {
         let svg = SVG{height: 80,
                       xdim: 1,
                       background: Color{rgba: [255, 0, 0, 255]},
-                      foreground: Color{rgba: [0, 0, 255, 255]}};
+                      foreground: Color{rgba: [0, 0, 255, 255]},
+                      render_mode: RenderMode::Fill,
+                      bar_width_reduction: 0.0,
+                      invert: false,
+                      left_quiet_zone: 0,
+                      right_quiet_zone: 0};
         let generated = svg.generate(&ean13.encode()[..]).unwrap();
 
         if WRITE_TO_FILE { write_file(&generated[..], "ean13_colored.svg"); }
@@ -193,7 +631,12 @@ mod tests {
         let svg = SVG{height: 70,
                       xdim: 1,
                       background: Color{rgba: [255, 0, 0, 128]},
-                      foreground: Color{rgba: [0, 0, 255, 128]}};
+                      foreground: Color{rgba: [0, 0, 255, 128]},
+                      render_mode: RenderMode::Fill,
+                      bar_width_reduction: 0.0,
+                      invert: false,
+                      left_quiet_zone: 0,
+                      right_quiet_zone: 0};
         let generated = svg.generate(&ean13.encode()[..]).unwrap();
 
         if WRITE_TO_FILE { write_file(&generated[..], "ean13_colored_semi_transparent.svg"); }
@@ -202,6 +645,24 @@ mod tests {
     }
 
 
+    #[test]
+    fn colored_modules_ean_13_as_svg() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let svg = SVG::new(80);
+        let red = Color::new([255, 0, 0, 255]);
+        let blue = Color::new([0, 0, 255, 255]);
+        let generated = svg.generate_with_colors(&ean13.encode()[..], &[(0..3, red), (2..5, blue), (90..200, red)]).unwrap();
+
+        if WRITE_TO_FILE { write_file(&generated[..], "ean13_colored_modules.svg"); }
+
+        assert!(generated.contains("<rect x=\"0\" y=\"0\" width=\"1\" height=\"80\" fill=\"#ff0000\"/><rect x=\"2\""));
+        assert!(generated.contains("<rect x=\"2\" y=\"0\" width=\"1\" height=\"80\" fill=\"#0000ff\"/>"));
+        assert!(generated.contains("<rect x=\"4\" y=\"0\" width=\"1\" height=\"80\" fill=\"#0000ff\"/>"));
+        assert!(generated.contains("<rect x=\"5\" y=\"0\" width=\"1\" height=\"80\" fill=\"#000000\"/>"));
+        assert!(generated.contains("<rect x=\"94\" y=\"0\" width=\"1\" height=\"80\" fill=\"#ff0000\"/></svg>"));
+        assert_eq!(generated.len(), 2890);
+    }
+
     #[test]
     fn ean_8_as_svg() {
         let ean8 = EAN8::new("9998823").unwrap();
@@ -274,7 +735,12 @@ mod tests {
         let svg = SVG{height: 80,
                       xdim: 1,
                       background: Color::black(),
-                      foreground: Color::white()};
+                      foreground: Color::white(),
+                      render_mode: RenderMode::Fill,
+                      bar_width_reduction: 0.0,
+                      invert: false,
+                      left_quiet_zone: 0,
+                      right_quiet_zone: 0};
         let generated = svg.generate(&itf.encode()[..]).unwrap();
 
         if WRITE_TO_FILE { write_file(&generated[..], "itf.svg"); }
@@ -282,13 +748,53 @@ mod tests {
         assert_eq!(generated.len(), 7123);
     }
 
+    #[test]
+    fn ean_13_as_svg_with_physical_width() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let svg = SVG::new(80).with_physical_width(&ean13, 37.29, 300);
+        let width_mm = svg.total_width_mm(&ean13, 300);
+
+        assert_eq!(svg.xdim, 4);
+        assert!((width_mm - 32.173).abs() < 0.001);
+        assert_eq!(SVG::new(80).with_physical_width(&ean13, width_mm, 300).xdim, 4);
+    }
+
+    #[test]
+    fn ean_13_as_svg_with_physical_width_and_quiet_zones() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let svg = SVG{left_quiet_zone: 11, right_quiet_zone: 7, ..SVG::new(80)}.with_physical_width(&ean13, 37.29, 300);
+        let width_mm = svg.total_width_mm(&ean13, 300);
+        let generated = svg.generate(&ean13.encode()[..]).unwrap();
+
+        // The 95 modules and 18 quiet modules are 113 modules in all.
+        assert_eq!(svg.xdim, 3);
+        assert!((width_mm - 28.702).abs() < 0.001);
+        assert!(generated.starts_with("<svg version=\"1.1\" viewBox=\"0 0 339 80\">"));
+        assert_eq!(SVG{left_quiet_zone: 11, right_quiet_zone: 7, ..SVG::new(80)}.with_physical_width(&ean13, width_mm, 300).xdim, 3);
+    }
+
+    #[test]
+    fn ean_13_as_svg_with_min_height() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let svg = SVG::new(0).with_min_height(&ean13);
+        let generated = svg.generate(&ean13.encode()[..]).unwrap();
+
+        assert_eq!(svg.height, 70);
+        assert!(generated.starts_with("<svg version=\"1.1\" viewBox=\"0 0 95 70\">"));
+    }
+
     #[test]
     fn code11_as_svg() {
         let code11 = Code11::new("9988-45643201").unwrap();
         let svg = SVG{height: 80,
                       xdim: 1,
                       background: Color::black(),
-                      foreground: Color::white()};
+                      foreground: Color::white(),
+                      render_mode: RenderMode::Fill,
+                      bar_width_reduction: 0.0,
+                      invert: false,
+                      left_quiet_zone: 0,
+                      right_quiet_zone: 0};
         let generated = svg.generate(&code11.encode()[..]).unwrap();
 
         if WRITE_TO_FILE { write_file(&generated[..], "code11.svg"); }
@@ -296,4 +802,187 @@ mod tests {
         assert_eq!(generated.len(), 4219);
     }
 
+    #[test]
+    fn stroked_ean_13_as_svg() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let svg = SVG{render_mode: RenderMode::Stroke{width: 1, color: Color::black()},
+                      ..SVG::new(80)};
+        let generated = svg.generate(&ean13.encode()[..]).unwrap();
+
+        if WRITE_TO_FILE { write_file(&generated[..], "ean13_stroked.svg"); }
+
+        // The background, plus one outline per bar.
+        assert_eq!(generated.matches("<rect").count(), 31);
+        assert_eq!(generated.matches("fill=\"none\" stroke=\"#000000\" stroke-width=\"1\"").count(), 30);
+        assert!(generated.contains("<rect x=\"0\" y=\"0\" width=\"1\" height=\"80\" fill=\"none\""));
+        assert!(generated.contains("width=\"2\" height=\"80\" fill=\"none\""));
+    }
+
+    #[test]
+    fn tiled_ean_8_as_svg() {
+        let data = ["1234567", "9876543", "5512345", "1111111"];
+        let barcodes: Vec<Vec<u8>> = data.iter().map(|d| EAN8::new(d).unwrap().encode()).collect();
+        let svg = SVG::new(40);
+        let generated = svg.tile(&barcodes, 2, 10).unwrap();
+
+        if WRITE_TO_FILE { write_file(&generated[..], "ean8_tiled.svg"); }
+
+        // 2 * 67 modules plus 3 gaps wide, 2 * 40 pixels plus 3 gaps high.
+        assert!(generated.starts_with("<svg version=\"1.1\" viewBox=\"0 0 164 110\"><rect x=\"0\" y=\"0\" width=\"164\" height=\"110\""));
+        assert!(generated.contains("<g transform=\"translate(87 60)\">"));
+        assert_eq!(generated.matches("<g ").count(), 4);
+        assert_eq!(svg.tile(&barcodes, 0, 10).err(), Some(Error::Generate));
+    }
+
+    #[test]
+    fn reduced_ean_13_as_svg() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let svg = SVG{xdim: 2, bar_width_reduction: 0.5, ..SVG::new(80)};
+        let generated = svg.generate(&ean13.encode()[..]).unwrap();
+
+        if WRITE_TO_FILE { write_file(&generated[..], "ean13_reduced.svg"); }
+
+        // The guard bars are narrowed by 0.5 pixels but stay centered on their modules.
+        assert!(generated.contains("<rect x=\"0.25\" y=\"0\" width=\"1.5\" height=\"80\""));
+        assert!(generated.contains("<rect x=\"4.25\" y=\"0\" width=\"1.5\" height=\"80\""));
+        assert_eq!(generated.matches("<rect").count(), 31);
+    }
+
+    #[test]
+    fn ean_13_as_svg_with_text() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let svg = SVG::new(80);
+        let encoded = ean13.encode();
+        let text = [(0..3, "7"), (3..45, "501031"), (50..92, "311300")];
+        let below = svg.generate_with_text(&encoded, &text, TextOptions::default()).unwrap();
+        let above = svg.generate_with_text(&encoded, &text, TextOptions{position: TextPosition::Above,
+                                                                         ..TextOptions::default()}).unwrap();
+
+        if WRITE_TO_FILE { write_file(&below[..], "ean13_text.svg"); }
+
+        assert!(below.starts_with("<svg version=\"1.1\" viewBox=\"0 0 95 92\">"));
+        assert!(below.contains("<g transform=\"translate(0 0)\">"));
+        assert!(below.contains("<text x=\"24\" y=\"92\" font-family=\"monospace\" font-size=\"10\" text-anchor=\"middle\" fill=\"#000000\">501031</text>"));
+        assert!(above.contains("<g transform=\"translate(0 12)\">"));
+        assert!(above.contains("<text x=\"71\" y=\"10\""));
+        assert_eq!(above.matches("<text").count(), 3);
+    }
+
+    #[test]
+    fn ean_13_as_svg_from_barcode() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let svg = SVG::new(80);
+        let generated = svg.generate_from(&ean13).unwrap();
+        let guards = generated.split("<g transform=\"translate(0 80)\">").nth(1).unwrap();
+
+        if WRITE_TO_FILE { write_file(&generated[..], "ean13_from.svg"); }
+
+        assert!(generated.starts_with("<svg version=\"1.1\" viewBox=\"0 0 95 92\">"));
+        assert!(generated.contains(">7</text>"));
+        assert!(generated.contains(">311309</text>"));
+        // The six guard bars are extended below the others, short of the text baseline.
+        assert_eq!(guards.split("</g>").next().unwrap().matches("height=\"7\"").count(), 6);
+        assert!(guards.contains("<rect x=\"46\" y=\"0\" width=\"1\" height=\"7\""));
+
+        let code39 = Code39::new("ABC").unwrap();
+
+        assert_eq!(svg.generate_from(&code39).unwrap(), svg.generate(code39.encode()).unwrap());
+    }
+
+    #[test]
+    fn ean_13_as_svg_symbol() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let svg = SVG::new(80);
+        let symbol = svg.generate_symbol(ean13.encode(), "ean13").unwrap();
+        let uses: String = (0..3).map(|i| symbol.use_at(0, i * 100)).collect();
+        let generated = format!("<svg version=\"1.1\" viewBox=\"0 0 95 280\">{}{}</svg>", symbol.defs(), uses);
+        let bars = svg.generate(ean13.encode()).unwrap();
+        let first_bar = bars.split("/>").nth(1).unwrap();
+
+        if WRITE_TO_FILE { write_file(&generated[..], "ean13_symbol.svg"); }
+
+        assert_eq!(generated.matches("<symbol").count(), 1);
+        assert_eq!(generated.matches(first_bar).count(), 1);
+        assert_eq!(generated.matches("<use href=\"#ean13\"").count(), 3);
+        assert!(generated.contains("<use href=\"#ean13\" x=\"0\" y=\"200\"/>"));
+        assert!(svg.generate_symbol(ean13.encode(), "").is_err());
+    }
+
+    #[test]
+    fn stacked_svg() {
+        let code128 = Code128::new("ÀHELLO").unwrap().encode();
+        let ean13 = EAN13::new("750103131130").unwrap().encode();
+        let svg = SVG::new(40);
+        let generated = svg.stack(&[(code128.clone(), Some("HELLO".to_string())), (ean13, None)], 5).unwrap();
+
+        if WRITE_TO_FILE { write_file(&generated[..], "stacked.svg"); }
+
+        // The EAN-13 is the wider (95 modules) of the two, with a quiet zone of 10 on each side.
+        assert!(generated.starts_with("<svg version=\"1.1\" viewBox=\"0 0 115 107\">"));
+        assert_eq!(generated.matches("<g transform").count(), 2);
+        assert!(generated.contains(&format!("<g transform=\"translate({} 5)\">", format_px(10.0 + ((95 - code128.len()) as f64 / 2.0)))));
+        // The caption of the Code128 (12 pixels in all) pushes the EAN-13 down.
+        assert!(generated.contains("<g transform=\"translate(10 62)\">"));
+        assert!(generated.contains(">HELLO</text></g>"));
+        assert_eq!(svg.stack(&[], 5).unwrap(), "<svg version=\"1.1\" viewBox=\"0 0 20 5\"><rect x=\"0\" y=\"0\" width=\"20\" height=\"5\" fill=\"#ffffff\"/></svg>");
+    }
+
+    #[test]
+    fn code128_as_svg_with_colored_text() {
+        let code128 = Code128::new("ƁA&B").unwrap();
+        let svg = SVG::new(40);
+        let encoded = code128.encode();
+        let options = TextOptions{color: Color::new([255, 0, 0, 128]), ..TextOptions::default()};
+        let generated = svg.generate_with_text(&encoded, &[(0..encoded.len(), "A&B")], options).unwrap();
+        let hidden = svg.generate_with_text(&encoded, &[(0..encoded.len(), "A&B")],
+                                            TextOptions{position: TextPosition::None, ..options}).unwrap();
+
+        assert!(generated.contains("fill=\"#ff0000\" fill-opacity=\"0.50\">A&amp;B</text>"));
+        assert_eq!(hidden, svg.generate(&encoded).unwrap());
+    }
+
+    #[test]
+    fn inverted_ean_8_as_svg() {
+        let ean8 = EAN8::new("1234567").unwrap();
+        let svg = SVG{invert: true, ..SVG::new(20)};
+        let generated = svg.generate(ean8.encode()).unwrap();
+        let stacked = svg.stack(&[(ean8.encode(), None)], 5).unwrap();
+
+        // The first guard bar is now background, and the space after it foreground.
+        assert!(!generated.contains("<rect x=\"0\" y=\"0\" width=\"1\""));
+        assert!(generated.contains("<rect x=\"1\" y=\"0\" width=\"1\" height=\"20\" fill=\"#000000\"/>"));
+        // The quiet zone around the stacked barcode is left as background.
+        assert!(stacked.starts_with("<svg version=\"1.1\" viewBox=\"0 0 87 30\"><rect x=\"0\" y=\"0\" width=\"87\" height=\"30\" fill=\"#ffffff\"/><g transform=\"translate(10 5)\"><rect x=\"1\""));
+    }
+
+    #[test]
+    fn ean_8_with_quiet_zones_as_svg() {
+        let ean8 = EAN8::new("1234567").unwrap();
+        let svg = SVG{left_quiet_zone: 12, right_quiet_zone: 6, ..SVG::new(20)};
+        let generated = svg.generate(ean8.encode()).unwrap();
+        let labelled = svg.generate_from(&ean8).unwrap();
+
+        if WRITE_TO_FILE { write_file(&generated[..], "ean8_quiet_zones.svg"); }
+
+        assert!(generated.starts_with("<svg version=\"1.1\" viewBox=\"0 0 85 20\"><rect x=\"0\" y=\"0\" width=\"85\" height=\"20\" fill=\"#ffffff\"/><g transform=\"translate(12 0)\"><rect x=\"0\""));
+        assert!(labelled.contains("<g transform=\"translate(12 0)\">"));
+        assert!(labelled.contains("<g transform=\"translate(12 20)\">"));
+        assert_eq!(SVG::new(20).with_quiet_zone(&ean8).quiet_width(&ean8.encode()), 7 + 67 + 7);
+    }
+
+    #[test]
+    fn rm4scc_as_svg() {
+        let rm4scc = RM4SCC::new("SN34RD1A").unwrap();
+        let svg = SVG{xdim: 2, ..SVG::new(24)};
+        let generated = svg.generate_four_state(&rm4scc.encode()).unwrap();
+
+        if WRITE_TO_FILE { write_file(&generated[..], "rm4scc.svg"); }
+
+        // 38 bars, each followed by a space bar but the last.
+        assert!(generated.starts_with("<svg version=\"1.1\" viewBox=\"0 0 150 24\">"));
+        assert_eq!(generated.matches("<rect").count(), 39);
+        // The start bar is an ascender, and the stop bar is full.
+        assert!(generated.contains("<rect x=\"0\" y=\"0\" width=\"2\" height=\"16\" fill=\"#000000\"/>"));
+        assert!(generated.contains("<rect x=\"148\" y=\"0\" width=\"2\" height=\"24\" fill=\"#000000\"/>"));
+    }
 }
@@ -0,0 +1,153 @@
+//! Functionality for streaming the bars of a barcode to your own output backend.
+//!
+//! Rather than building an image or string, `render_with` calls a closure for each bar that
+//! would be drawn, which is useful for plotters, GPU buffers and the like. This is always
+//! available, as it doesn't require any other crates.
+//!
+//! For example:
+//!
+//! ```rust
+//! use barcoders::generators::visitor::*;
+//! use barcoders::sym::ean8::EAN8;
+//!
+//! let ean8 = EAN8::new("1234567").unwrap();
+//! let mut bars = vec![];
+//!
+//! render_with(&ean8.encode(), &RenderOptions::new(40.0), |bar| bars.push(bar));
+//!
+//! assert_eq!(bars[0], BarRect{x: 0.0, y: 0.0, width: 1.0, height: 40.0});
+//! ```
+
+use sym::helpers;
+use sym::postal::BarState;
+
+/// A bar to be drawn, positioned relative to the top-left corner of the barcode.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BarRect {
+    /// The left edge of the bar.
+    pub x: f64,
+    /// The top edge of the bar.
+    pub y: f64,
+    /// The width of the bar.
+    pub width: f64,
+    /// The height of the bar.
+    pub height: f64,
+}
+
+/// Options for rendering a barcode with `render_with`, in whichever units the backend uses.
+#[derive(Copy, Clone, Debug)]
+pub struct RenderOptions {
+    /// The height of the barcode.
+    pub height: f64,
+    /// The X dimension. Specifies the width of the "narrow" bars.
+    pub xdim: f64,
+    /// The amount to shave off each bar (split evenly between its two edges) to compensate for
+    /// ink spread, at most half the bar. The module pitch is unchanged.
+    pub bar_width_reduction: f64,
+}
+
+impl RenderOptions {
+    /// Returns new options with default values.
+    pub fn new(height: f64) -> RenderOptions {
+        RenderOptions {
+            height,
+            xdim: 1.0,
+            bar_width_reduction: 0.0,
+        }
+    }
+}
+
+/// Calls `visit` with each bar of the given barcode, from left to right. Adjacent modules are
+/// merged into a single bar.
+pub fn render_with<F: FnMut(BarRect)>(barcode: &[u8], opts: &RenderOptions, mut visit: F) {
+    let mut offset = 0.0;
+
+    for (dark, len) in helpers::modules_to_rle(barcode) {
+        let width = f64::from(len) * opts.xdim;
+
+        if dark {
+            let shaved = opts.bar_width_reduction.max(0.0).min(width / 2.0);
+
+            visit(BarRect {
+                x: offset + (shaved / 2.0),
+                y: 0.0,
+                width: width - shaved,
+                height: opts.height,
+            });
+        }
+
+        offset += width;
+    }
+}
+
+/// Calls `visit` with each bar of the given 4-state barcode (see `sym::postal`), from left to
+/// right. Each bar is one X dimension wide and followed by a space of the same width, while its
+/// vertical extent follows `BarState::extent`.
+pub fn render_four_state<F: FnMut(BarRect)>(bars: &[BarState], opts: &RenderOptions, mut visit: F) {
+    let shaved = opts.bar_width_reduction.max(0.0).min(opts.xdim / 2.0);
+
+    for (i, bar) in bars.iter().enumerate() {
+        let extent = bar.extent();
+
+        visit(BarRect {
+            x: (i as f64 * 2.0 * opts.xdim) + (shaved / 2.0),
+            y: extent.start * opts.height,
+            width: opts.xdim - shaved,
+            height: (extent.end - extent.start) * opts.height,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sym::ean8::*;
+    use sym::postal::*;
+    use generators::visitor::*;
+
+    #[test]
+    fn ean_8_bars() {
+        let ean8 = EAN8::new("1234567").unwrap();
+        let opts = RenderOptions{xdim: 2.0, ..RenderOptions::new(30.0)};
+        let mut bars = vec![];
+
+        render_with(&ean8.encode(), &opts, |bar| bars.push(bar));
+
+        // The left guard (101) and the first digit, '1' (0011001).
+        assert_eq!(bars[..4], [BarRect{x: 0.0, y: 0.0, width: 2.0, height: 30.0},
+                               BarRect{x: 4.0, y: 0.0, width: 2.0, height: 30.0},
+                               BarRect{x: 10.0, y: 0.0, width: 4.0, height: 30.0},
+                               BarRect{x: 18.0, y: 0.0, width: 2.0, height: 30.0}]);
+        assert_eq!(bars.last(), Some(&BarRect{x: 132.0, y: 0.0, width: 2.0, height: 30.0}));
+        assert_eq!(bars.len(), count_bars(&ean8.encode()));
+    }
+
+    #[test]
+    fn reduced_bars() {
+        let opts = RenderOptions{bar_width_reduction: 0.5, ..RenderOptions::new(10.0)};
+        let mut bars = vec![];
+
+        render_with(&[1, 1, 0, 1], &opts, |bar| bars.push(bar));
+
+        assert_eq!(bars, vec![BarRect{x: 0.25, y: 0.0, width: 1.5, height: 10.0},
+                              BarRect{x: 3.25, y: 0.0, width: 0.5, height: 10.0}]);
+    }
+
+    #[test]
+    fn four_state_bars() {
+        let kix = KIX::new("0").unwrap();
+        let opts = RenderOptions{xdim: 2.0, ..RenderOptions::new(30.0)};
+        let mut bars = vec![];
+
+        render_four_state(&kix.encode(), &opts, |bar| bars.push(bar));
+
+        // '0' is two trackers followed by two full bars.
+        assert_eq!(bars, vec![BarRect{x: 0.0, y: 10.0, width: 2.0, height: 10.0},
+                              BarRect{x: 4.0, y: 10.0, width: 2.0, height: 10.0},
+                              BarRect{x: 8.0, y: 0.0, width: 2.0, height: 30.0},
+                              BarRect{x: 12.0, y: 0.0, width: 2.0, height: 30.0}]);
+    }
+
+    fn count_bars(modules: &[u8]) -> usize {
+        modules.windows(2).filter(|w| w[0] == 0 && w[1] == 1).count() + (modules[0] as usize)
+    }
+}
@@ -22,7 +22,12 @@
 //! * Two-Of-Five
 //!   * Interleaved (ITF)
 //!   * Standard (STF)
+//! * MSI
+//! * Pharmacode
 //! * Codabar
+//! * 4-State Postal
+//!   * KIX
+//!   * RM4SCC
 //! * More coming!
 //!
 //! ### Generators
@@ -36,6 +41,8 @@
 //! * PNG (feature: `image`)
 //! * GIF (feature: `image`)
 //! * JPEG (feature: `image`)
+//! * tiny-skia Pixmap (feature: `tiny-skia`)
+//! * Your own backend, via `generators::visitor`
 //! * Or add your own
 //!
 //! ## Examples
@@ -56,6 +63,67 @@
 #[cfg(feature = "image")]
 extern crate image;
 
+#[cfg(feature = "tiny-skia")]
+extern crate tiny_skia;
+
 pub mod error;
+pub mod checksum;
 pub mod sym;
 pub mod generators;
+
+#[cfg(feature = "svg")]
+use generators::svg::SVG;
+
+/// Encodes the given data with the named symbology and generates it as an SVG, in one call.
+///
+/// If no SVG generator is given, a default one is used at the recommended minimum height for the
+/// barcode. Returns a `Result<String, Error>` of the SVG data, or any error that occurred while
+/// parsing the symbology, encoding the data or generating the SVG.
+///
+/// ```rust
+/// let svg = barcoders::to_svg("code39", "BARCODERS", None).unwrap();
+///
+/// assert!(svg.starts_with("<svg"));
+/// ```
+#[cfg(feature = "svg")]
+pub fn to_svg<S: AsRef<str>, T: AsRef<str>>(symbology: S, data: T, svg: Option<SVG>) -> error::Result<String> {
+    let symbology: sym::Symbology = symbology.as_ref().parse()?;
+    let barcode = symbology.barcode(data)?;
+    let svg = match svg {
+        Some(svg) => svg,
+        None => SVG::new(0).with_min_height(&*barcode),
+    };
+
+    svg.generate(barcode.encode())
+}
+
+#[cfg(test)]
+#[cfg(feature = "svg")]
+mod tests {
+    use ::to_svg;
+    use error::Error;
+    use sym::ean13::EAN13;
+    use generators::svg::SVG;
+
+    #[test]
+    fn code128_to_svg() {
+        let generated = to_svg("code128", "ÀHELLO", None).unwrap();
+
+        assert!(generated.starts_with("<svg version=\"1.1\" viewBox=\"0 0 90 14\">"));
+    }
+
+    #[test]
+    fn ean13_to_svg() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let generated = to_svg("EAN-13", "750103131130", Some(SVG::new(80))).unwrap();
+
+        assert_eq!(generated, SVG::new(80).generate(&ean13.encode()[..]).unwrap());
+    }
+
+    #[test]
+    fn invalid_to_svg() {
+        assert_eq!(to_svg("code1000", "1234", None).err().unwrap(), Error::Symbology);
+        assert_eq!(to_svg("ean13", "1234", None).err().unwrap(), Error::Length);
+        assert_eq!(to_svg("code39", "hello", None).err().unwrap(), Error::Character);
+    }
+}
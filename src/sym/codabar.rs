@@ -4,11 +4,14 @@
 //!
 //! Codabar is used in the USA by FedEx, some Hospitals, and photo labs.
 //!
-//! Barcodes of this variant should start and end with either A, B, C, or D depending on
-//! the industry.
+//! Barcodes of this variant must start and end with either A, B, C, or D depending on
+//! the industry. These start/stop characters may not appear elsewhere in the data.
+//!
+//! For example, "A1234B" starts with A and stops with B, whilst "C1234D" encodes the same data
+//! framed by C and D.
 
-use sym::Parse;
-use error::Result;
+use sym::{Barcode, Density, Parse, helpers};
+use error::{Error, Result};
 use std::ops::Range;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -48,6 +51,10 @@ impl Unit {
         }
     }
 
+    fn is_guard(self) -> bool {
+        matches!(self, Unit::A | Unit::B | Unit::C | Unit::D)
+    }
+
     fn from_char(c: char) -> Option<Unit> {
         match c {
             '0' => Some(Unit::Zero),
@@ -75,20 +82,66 @@ impl Unit {
     }
 }
 
+// Characters are separated by a single "narrow" space, unless a wider separator was requested.
+const SEPARATOR_WIDTH: usize = 1;
+
 /// The Codabar barcode type.
 #[derive(Debug)]
-pub struct Codabar(Vec<Unit>);
+pub struct Codabar(Vec<Unit>, usize);
 
 impl Codabar {
     /// Creates a new barcode.
+    /// The data must start and end with one of the start/stop characters (A, B, C or D).
     /// Returns Result<Codabar, Error> indicating parse success.
     pub fn new<T: AsRef<str>>(data: T) -> Result<Codabar> {
         let d = Codabar::parse(data.as_ref())?;
         let units = d.chars()
-                     .map(|c| Unit::from_char(c).unwrap())
-                     .collect();
+                     .map(|c| Unit::from_char(c).ok_or(Error::Character))
+                     .collect::<Result<Vec<Unit>>>()?;
+
+        Codabar::check_guards(&units)?;
 
-        Ok(Codabar(units))
+        Ok(Codabar(units, SEPARATOR_WIDTH))
+    }
+
+    /// Returns this barcode with a gap of the given number of modules between characters,
+    /// rather than the standard single module. Some printers need wider gaps, which scanners
+    /// tolerate as Codabar is discrete.
+    /// Returns Result<Codabar, Error>, failing with `Error::Length` if the width is zero.
+    pub fn with_separator_width(self, width: usize) -> Result<Codabar> {
+        match width {
+            0 => Err(Error::Length),
+            w => Ok(Codabar(self.0, w)),
+        }
+    }
+
+    // Ensures the data is framed by a start and stop character and that neither
+    // appears within the body.
+    fn check_guards(units: &[Unit]) -> Result<()> {
+        match units.split_first() {
+            Some((start, rest)) => match rest.split_last() {
+                Some((stop, body)) if start.is_guard() && stop.is_guard() &&
+                                      !body.iter().any(|u| u.is_guard()) => Ok(()),
+                _ => Err(Error::Character),
+            },
+            None => Err(Error::Length),
+        }
+    }
+
+    // Confirms that the given encoding is made up of this barcode's characters, separated by
+    // gaps. The characters vary in width, so they are told apart by their seven bars and spaces.
+    fn check(&self, modules: &[u8]) -> Result<()> {
+        let units: Vec<Unit> = Codabar::valid_chars().into_iter().filter_map(Unit::from_char).collect();
+        let decoded = helpers::split_discrete(modules, 7, self.1)?
+            .iter()
+            .map(|p| units.iter().cloned().find(|u| u.lookup() == *p).ok_or(Error::Generate))
+            .collect::<Result<Vec<Unit>>>()?;
+
+        if decoded != self.0 {
+            return Err(Error::Generate);
+        }
+
+        Ok(())
     }
 
     /// Encodes the barcode.
@@ -101,8 +154,8 @@ impl Codabar {
                         .iter()
                         .cloned());
 
-            if i < self.0.len() - 1 {
-                enc.push(0);
+            if i + 1 < self.0.len() {
+                enc.extend(vec![0; self.1]);
             }
         }
 
@@ -124,9 +177,28 @@ impl Parse for Codabar {
     }
 }
 
+impl Barcode for Codabar {
+    fn encode(&self) -> Vec<u8> {
+        Codabar::encode(self)
+    }
+
+    /// The start and stop characters, along with the gaps between the characters, are
+    /// overhead.
+    fn density(&self) -> Density {
+        let data = self.0[1..self.0.len() - 1].iter().map(|u| u.lookup().len()).sum();
+
+        Density::new(self.encode().len(), data, self.metadata().quiet_zone_modules)
+    }
+
+    fn self_check(&self) -> Result<()> {
+        self.check(&self.encode())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use sym::codabar::*;
+    use sym::Barcode;
     use error::Error;
     use std::char;
 
@@ -149,6 +221,35 @@ mod tests {
         assert_eq!(codabar.err().unwrap(), Error::Character);
     }
 
+    #[test]
+    fn invalid_guards_codabar() {
+        let codabar_a = Codabar::new("12345B"); // Missing start.
+        let codabar_b = Codabar::new("A12345"); // Missing stop.
+        let codabar_c = Codabar::new("A12C45B"); // Start/stop character in the body.
+        let codabar_d = Codabar::new("A"); // Start without stop.
+
+        assert_eq!(codabar_a.err().unwrap(), Error::Character);
+        assert_eq!(codabar_b.err().unwrap(), Error::Character);
+        assert_eq!(codabar_c.err().unwrap(), Error::Character);
+        assert_eq!(codabar_d.err().unwrap(), Error::Character);
+    }
+
+    #[test]
+    fn codabar_encode_custom_guards() {
+        let codabar_ab = collapse_vec(Codabar::new("A1234B").unwrap().encode());
+        let codabar_cd = collapse_vec(Codabar::new("C1234D").unwrap().encode());
+
+        assert_eq!(codabar_ab, "1011001001010101100101010010110110010101010110100101010010011");
+        assert_eq!(codabar_cd, "1001001011010101100101010010110110010101010110100101010011001");
+
+        // Same body, differing framing.
+        assert_eq!(codabar_ab[11..50], codabar_cd[11..50]);
+        assert!(codabar_ab.starts_with("1011001001"));
+        assert!(codabar_cd.starts_with("1001001011"));
+        assert!(codabar_ab.ends_with("1010010011"));
+        assert!(codabar_cd.ends_with("1010011001"));
+    }
+
     #[test]
     fn codabar_encode() {
         let codabar_a = Codabar::new("A1234B").unwrap();
@@ -157,4 +258,31 @@ mod tests {
         assert_eq!(collapse_vec(codabar_a.encode()), "1011001001010101100101010010110110010101010110100101010010011");
         assert_eq!(collapse_vec(codabar_b.encode()), "10110010010101101001010101001101010110010110101001010010101101010010011");
     }
+
+    #[test]
+    fn codabar_with_separator_width() {
+        let plain = Codabar::new("A98B").unwrap().encode();
+        let encoded = Codabar::new("A98B").unwrap().with_separator_width(2).unwrap().encode();
+
+        // Each of the three gaps grows by a module.
+        assert_eq!(encoded.len(), plain.len() + 3);
+        assert_eq!(encoded[10..12], [0, 0]);
+        assert_eq!(encoded[..10], plain[..10]);
+        assert_eq!(encoded[12..19], plain[11..18]);
+        assert_eq!(Codabar::new("A98B").unwrap().with_separator_width(2).unwrap().self_check(), Ok(()));
+        assert_eq!(Codabar::new("A98B").unwrap().with_separator_width(0).err().unwrap(), Error::Length);
+    }
+
+    #[test]
+    fn codabar_self_check() {
+        let codabar = Codabar::new("A40156B").unwrap();
+        let mut corrupted = codabar.encode();
+
+        // Swap the encoding of the '4' for that of a '5', as a bad table would.
+        corrupted[11..20].copy_from_slice(&Unit::Five.lookup());
+
+        assert_eq!(codabar.self_check(), Ok(()));
+        assert_eq!(codabar.check(&corrupted), Err(Error::Generate));
+        assert_eq!(codabar.check(&codabar.encode()[11..]), Err(Error::Generate));
+    }
 }
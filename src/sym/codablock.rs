@@ -0,0 +1,300 @@
+//! Encoder for Codablock-F barcodes.
+//!
+//! Codablock-F is a stacked symbology, made up of between 2 and 44 rows of Code128. Each row
+//! begins with a row indicator character and is independently checked with the standard Code128
+//! checksum, while two further check characters (K1 and K2) at the end of the last row cover
+//! the data as a whole.
+//!
+//! Every row holds the same number of data characters (the columns), so that the rows line up.
+//! Data is encoded using character-set B, with ASCII control characters reached via SHIFT.
+//! Unused positions at the end of a row are filled with character-set switches, which carry no
+//! data.
+//!
+//! NOTE: Digits are not yet compacted into character-set C, and the generators do not draw the
+//! separator bars between rows.
+
+use sym::code128::Code128;
+use error::{Error, Result};
+
+use std::ops::RangeInclusive;
+
+const START_A: usize = 103;
+const CODE_B: usize = 100;
+const CODE_C: usize = 99;
+const SHIFT: usize = 98;
+
+const VALID_ROWS: RangeInclusive<usize> = 2..=44;
+const VALID_COLUMNS: RangeInclusive<usize> = 4..=62;
+
+/// Constraints on the size of a Codablock-F barcode. See `CodablockF::fits`.
+///
+/// These are specific to Codablock-F's rows and columns. The matrix symbologies are sized by
+/// version and error correction level instead, and will need their own constraints.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Constraints {
+    /// The number of data characters in each row (4 to 62), or None to choose the smallest
+    /// barcode that fits.
+    pub columns: Option<usize>,
+    /// The maximum number of rows (2 to 44).
+    pub max_rows: usize,
+}
+
+/// The size of a Codablock-F barcode holding some data. See `CodablockF::fits`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FitReport {
+    /// The number of rows.
+    pub rows: usize,
+    /// The number of data characters in each row.
+    pub columns: usize,
+    /// The number of unused symbol positions, which are filled with padding.
+    pub remaining: usize,
+}
+
+/// The Codablock-F barcode type.
+///
+/// As a stacked symbology, it doesn't implement `Barcode`, so there is no `self_check` for it
+/// and its rows are never decoded.
+#[derive(Debug)]
+pub struct CodablockF {
+    // The symbol values of each row, from the START symbol up to (but excluding) the row check.
+    rows: Vec<Vec<usize>>,
+}
+
+impl CodablockF {
+    /// Creates a new barcode, choosing the number of columns such that there are roughly as
+    /// many rows as columns.
+    /// Returns Result<CodablockF, Error> indicating parse success.
+    pub fn new<T: AsRef<str>>(data: T) -> Result<CodablockF> {
+        let data = data.as_ref();
+        let symbols = CodablockF::data_values(data)?.len() + 2;
+        let columns = ((symbols as f64).sqrt().ceil() as usize).clamp(*VALID_COLUMNS.start(),
+                                                                      *VALID_COLUMNS.end());
+
+        CodablockF::with_columns(data, columns)
+    }
+
+    /// Creates a new barcode with the given number of data characters (4 to 62) in each row.
+    /// Returns Result<CodablockF, Error> indicating parse success.
+    pub fn with_columns<T: AsRef<str>>(data: T, columns: usize) -> Result<CodablockF> {
+        let data = data.as_ref();
+        let bodies = CodablockF::layout(data, columns)?;
+        let (k1, k2) = CodablockF::check_values(data);
+        let row_count = bodies.len();
+        let rows = bodies.into_iter()
+                         .enumerate()
+                         .map(|(i, mut body)| {
+                             let mut padding = [CODE_C, CODE_B].iter().cycle();
+                             let data_columns = if i == row_count - 1 { columns - 2 } else { columns };
+
+                             while body.len() < data_columns {
+                                 body.extend(padding.next());
+                             }
+
+                             if i == row_count - 1 {
+                                 body.push(k1);
+                                 body.push(k2);
+                             }
+
+                             let mut row = vec![START_A, CodablockF::row_indicator(i, row_count), CODE_B];
+                             row.extend(body);
+                             row
+                         })
+                         .collect();
+
+        Ok(CodablockF{rows})
+    }
+
+    /// Finds the size of the smallest barcode (by number of symbols) that holds the given data
+    /// within the constraints, without encoding it. Ties go to the barcode with fewer columns.
+    /// Returns Result<FitReport, Error>, failing with `Error::Length` if the data doesn't fit.
+    pub fn fits<T: AsRef<str>>(data: T, constraints: Constraints) -> Result<FitReport> {
+        let data = data.as_ref();
+        let candidates = match constraints.columns {
+            Some(c) => c..=c,
+            None => VALID_COLUMNS,
+        };
+        let mut best: Option<FitReport> = None;
+
+        for columns in candidates {
+            let bodies = match CodablockF::layout(data, columns) {
+                Ok(b) => b,
+                Err(Error::Length) => continue,
+                Err(e) => return Err(e),
+            };
+            let rows = bodies.len();
+            let used: usize = bodies.iter().map(|b| b.len()).sum();
+            let report = FitReport{rows, columns, remaining: (rows * columns) - 2 - used};
+
+            if rows <= constraints.max_rows && best.map_or(true, |b| rows * columns < b.rows * b.columns) {
+                best = Some(report);
+            }
+        }
+
+        best.ok_or(Error::Length)
+    }
+
+    // Splits the data into the unpadded bodies of each row, leaving room in the last row for
+    // the check characters.
+    fn layout(data: &str, columns: usize) -> Result<Vec<Vec<usize>>> {
+        if data.is_empty() || !VALID_COLUMNS.contains(&columns) {
+            return Err(Error::Length);
+        }
+
+        let mut bodies = CodablockF::pack(CodablockF::data_values(data)?, columns);
+
+        // The last row must have room for both check characters.
+        if bodies.last().map_or(true, |b| b.len() + 2 > columns) {
+            bodies.push(vec![]);
+        }
+
+        while bodies.len() < *VALID_ROWS.start() {
+            bodies.insert(bodies.len() - 1, vec![]);
+        }
+
+        if bodies.len() > *VALID_ROWS.end() {
+            return Err(Error::Length);
+        }
+
+        Ok(bodies)
+    }
+
+    /// Returns the number of rows in the barcode.
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    // The first row indicates the number of rows, the others their own position.
+    fn row_indicator(row: usize, row_count: usize) -> usize {
+        match row {
+            0 => row_count - 2,
+            n => n + 42,
+        }
+    }
+
+    // Converts each character into its character-set B symbol value(s).
+    fn data_values(data: &str) -> Result<Vec<Vec<usize>>> {
+        data.chars()
+            .map(|c| match c as u32 {
+                n @ 0..=31 => Ok(vec![SHIFT, n as usize + 64]),
+                n @ 32..=127 => Ok(vec![n as usize - 32]),
+                _ => Err(Error::Character),
+            })
+            .collect()
+    }
+
+    // Fills rows with whole characters, as a SHIFT may not be separated from its character.
+    fn pack(values: Vec<Vec<usize>>, columns: usize) -> Vec<Vec<usize>> {
+        let mut bodies: Vec<Vec<usize>> = vec![vec![]];
+
+        for v in values {
+            if bodies.last().map_or(0, |b| b.len()) + v.len() > columns {
+                bodies.push(vec![]);
+            }
+
+            if let Some(b) = bodies.last_mut() {
+                b.extend(v);
+            }
+        }
+
+        bodies
+    }
+
+    // Calculates the K1 and K2 check values over the data using weighted modulo-86 sums.
+    fn check_values(data: &str) -> (usize, usize) {
+        let (k1, k2) = data.bytes()
+                           .enumerate()
+                           .fold((0, 0), |(k1, k2), (i, b)| {
+                               (k1 + ((i + 1) * b as usize), k2 + (i * b as usize))
+                           });
+
+        (k1 % 86, k2 % 86)
+    }
+
+    /// Encodes the barcode.
+    /// Returns a Vec<Vec<u8>> of binary digits, one per row from top to bottom.
+    pub fn encode(&self) -> Vec<Vec<u8>> {
+        self.rows
+            .iter()
+            .map(|r| Code128::from_values(r).encode())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sym::codablock::*;
+    use sym::code128::Code128;
+    use error::Error;
+
+    #[test]
+    fn new_codablock_f() {
+        let codablock = CodablockF::new("CODABLOCK F TEST DATA 12345");
+
+        assert!(codablock.is_ok());
+        assert_eq!(codablock.unwrap().rows(), 5);
+    }
+
+    #[test]
+    fn invalid_codablock_f() {
+        assert_eq!(CodablockF::new("").err().unwrap(), Error::Length);
+        assert_eq!(CodablockF::new("CAFÉ").err().unwrap(), Error::Character);
+        assert_eq!(CodablockF::with_columns("DATA", 3).err().unwrap(), Error::Length);
+        assert_eq!(CodablockF::with_columns("A".repeat(62 * 44), 62).err().unwrap(), Error::Length);
+    }
+
+    #[test]
+    fn codablock_f_two_rows() {
+        let codablock = CodablockF::with_columns("CODABLOCK", 6).unwrap();
+
+        // START-A, row indicator, CODE-B, then six data columns. The last row ends with a
+        // padding character followed by K1 and K2.
+        assert_eq!(codablock.rows, vec![vec![103, 0, 100, 35, 47, 36, 33, 34, 44],
+                                        vec![103, 43, 100, 47, 35, 43, 99, 57, 17]]);
+    }
+
+    #[test]
+    fn codablock_f_shift_not_split() {
+        let codablock = CodablockF::with_columns("ABC\tDEF", 4).unwrap();
+
+        assert_eq!(codablock.rows[0][3..], [33, 34, 35, 99]);
+        assert_eq!(codablock.rows[1][3..], [98, 73, 36, 37]);
+        assert_eq!(codablock.rows[2][3..], [38, 99, 44, 60]);
+        assert_eq!(codablock.rows[0][1], 1);
+        assert_eq!(codablock.rows[2][1], 44);
+    }
+
+    #[test]
+    fn codablock_f_fits() {
+        let fixed = CodablockF::fits("CODABLOCK", Constraints{columns: Some(6), max_rows: 44}).unwrap();
+        let smallest = CodablockF::fits("CODABLOCK F TEST DATA 12345", Constraints{columns: None, max_rows: 2}).unwrap();
+
+        // Matches codablock_f_two_rows, where a single padding character precedes K1 and K2.
+        assert_eq!(fixed, FitReport{rows: 2, columns: 6, remaining: 1});
+        assert_eq!(smallest.rows, 2);
+        assert_eq!(smallest.columns, 15);
+        assert_eq!(smallest.remaining, 1);
+        assert_eq!(CodablockF::with_columns("CODABLOCK F TEST DATA 12345", 15).unwrap().rows(), 2);
+    }
+
+    #[test]
+    fn codablock_f_overflows() {
+        let data = "A".repeat(100);
+
+        assert_eq!(CodablockF::fits(&data, Constraints{columns: Some(4), max_rows: 10}).err().unwrap(), Error::Length);
+        assert_eq!(CodablockF::fits(&data, Constraints{columns: None, max_rows: 1}).err().unwrap(), Error::Length);
+        assert_eq!(CodablockF::fits("CAFÉ", Constraints{columns: None, max_rows: 44}).err().unwrap(), Error::Character);
+        assert!(CodablockF::fits(&data, Constraints{columns: None, max_rows: 2}).is_ok());
+    }
+
+    #[test]
+    fn codablock_f_encode() {
+        let codablock = CodablockF::with_columns("CODABLOCK", 6).unwrap();
+        let encoded = codablock.encode();
+
+        // Each row is a complete Code128 symbol, including its own check character.
+        assert_eq!(encoded.len(), 2);
+        assert_eq!(encoded[0], Code128::from_values(&codablock.rows[0]).encode());
+        assert_eq!(encoded[0].len(), encoded[1].len());
+        assert_eq!(encoded[0].len(), (11 * 11) + 2);
+    }
+}
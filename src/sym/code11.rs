@@ -6,8 +6,9 @@
 //! Code11 is a discrete symbology. This encoder always provides a C checksum. For barcodes longer
 //! than 10 characters, a second checksum digit (K) is appended.
 
-use sym::{Parse, helpers};
-use error::Result;
+use sym::{Barcode, Density, Parse, helpers};
+use checksum;
+use error::{Error, Result};
 use std::ops::Range;
 
 // Character -> Binary mappings for each of the allowable characters.
@@ -21,11 +22,12 @@ const CHARS: [(char, &[u8]); 11] = [
 
 // Code11 barcodes must start and end with a special character.
 const GUARD: [u8; 7] = [1,0,1,1,0,0,1];
-const SEPARATOR: [u8; 1] = [0];
+// Characters are followed by a single "narrow" space, unless a wider separator was requested.
+const SEPARATOR_WIDTH: usize = 1;
 
 /// The Code11 barcode type.
 #[derive(Debug)]
-pub struct Code11(Vec<char>);
+pub struct Code11(Vec<usize>, usize);
 
 /// The USD-8 barcode type.
 pub type USD8 = Code11;
@@ -34,77 +36,100 @@ impl Code11 {
     /// Creates a new barcode.
     /// Returns Result<Code11, Error> indicating parse success.
     pub fn new<T: AsRef<str>>(data: T) -> Result<Code11> {
-        Code11::parse(data.as_ref()).and_then(|d| {
-            Ok(Code11(d.chars()
-                       .collect()))
-        })
+        let d = Code11::parse(data.as_ref())?;
+
+        // Characters are stored as their position in the CHARS table so that encoding cannot
+        // fail.
+        d.chars()
+         .map(|c| CHARS.iter().position(|t| t.0 == c).ok_or(Error::Character))
+         .collect::<Result<Vec<usize>>>()
+         .map(|d| Code11(d, SEPARATOR_WIDTH))
     }
 
-    fn char_encoding(&self, c: char) -> &[u8] {
-        match CHARS.iter().find(|&ch| ch.0 == c) {
-            Some(&(_, enc)) => enc,
-            None => panic!(format!("Unknown char: {}", c)),
+    /// Returns this barcode with a gap of the given number of modules between characters,
+    /// rather than the standard single module. Some printers need wider gaps, which scanners
+    /// tolerate as Code11 is discrete.
+    /// Returns Result<Code11, Error>, failing with `Error::Length` if the width is zero.
+    pub fn with_separator_width(self, width: usize) -> Result<Code11> {
+        match width {
+            0 => Err(Error::Length),
+            w => Ok(Code11(self.0, w)),
         }
     }
 
-    /// Calculates a checksum character using a weighted modulo-11 algorithm.
-    fn checksum_char(&self, data: &[char], weight_threshold: usize) -> Option<char> {
-        let get_char_pos = |&c| CHARS.iter().position(|t| t.0 == c).unwrap();
-        let weight = |i| {
-            match i % weight_threshold {
-                0 => weight_threshold,
-                n => n,
-            }
-        };
-        let positions = data.iter().map(&get_char_pos);
-        let index = positions.rev()
-                             .enumerate()
-                             .fold(0, |acc, (i, pos)| acc + (weight(i + 1) * pos));
-
+    /// Calculates the position of a checksum character using a weighted modulo-11 algorithm.
+    fn checksum_index(&self, data: &[usize], weight_threshold: usize) -> usize {
         // Some sources suggest that the C checksum should use modulo-11, whilst the K
         // checksum should use modulo-9. But most generators always use modulo-11.
         // This algorithm currently just uses 11 for both checksums, but can be easily 
         // changed at a later date.
-        match CHARS.get(index % CHARS.len()) {
-            Some(&(c, _)) => Some(c),
-            None => None,
-        }
+        checksum::mod11(data, weight_threshold)
     }
 
-
-    /// Calculates the C checksum character using a weighted modulo-11 algorithm.
-    fn c_checksum_char(&self) -> Option<char> {
-        self.checksum_char(&self.0, 10)
+    /// Calculates the position of the C checksum character.
+    fn c_checksum_index(&self) -> usize {
+        self.checksum_index(&self.0, 10)
     }
 
-    /// Calculates the K checksum character using a weighted modulo-11 algorithm.
-    fn k_checksum_char(&self, c_checksum: char) -> Option<char> {
-        let mut data: Vec<char> = self.0.clone();
+    /// Calculates the position of the K checksum character.
+    fn k_checksum_index(&self, c_checksum: usize) -> usize {
+        let mut data: Vec<usize> = self.0.clone();
         data.push(c_checksum);
 
-        self.checksum_char(&data, 9)
+        self.checksum_index(&data, 9)
+    }
+
+    // Confirms that the given encoding is made up of this barcode's characters and checksums,
+    // each followed by a gap, between the two guards. The characters vary in width, so they are
+    // told apart by their five bars and spaces.
+    fn check(&self, modules: &[u8]) -> Result<()> {
+        let patterns = helpers::split_discrete(modules, 5, self.1)?;
+
+        match (patterns.first(), patterns.last()) {
+            (Some(start), Some(end)) if patterns.len() > 1 && start[..] == GUARD && end[..] == GUARD => (),
+            _ => return Err(Error::Generate),
+        }
+
+        let indices = patterns[1..patterns.len() - 1]
+            .iter()
+            .map(|p| CHARS.iter().position(|t| t.1 == &p[..]).ok_or(Error::Generate))
+            .collect::<Result<Vec<usize>>>()?;
+        let c_checksum = self.c_checksum_index();
+        let mut expected = self.0.clone();
+
+        expected.push(c_checksum);
+
+        if self.0.len() > 10 {
+            expected.push(self.k_checksum_index(c_checksum));
+        }
+
+        if indices != expected {
+            return Err(Error::Generate);
+        }
+
+        Ok(())
     }
 
     fn push_encoding(&self, into: &mut Vec<u8>, from: &[u8]) {
         into.extend(from.iter().cloned());
-        into.extend(&SEPARATOR);
+        into.extend(vec![0; self.1]);
     }
 
     fn payload(&self) -> Vec<u8> {
         let mut enc = vec![];
-        let c_checksum = self.c_checksum_char().expect("Cannot compute checksum C");
+        let c_checksum = self.c_checksum_index();
 
-        for &c in &self.0 {
-            self.push_encoding(&mut enc, self.char_encoding(c));
+        for &i in &self.0 {
+            self.push_encoding(&mut enc, CHARS[i].1);
         }
 
-        self.push_encoding(&mut enc, self.char_encoding(c_checksum));
+        self.push_encoding(&mut enc, CHARS[c_checksum].1);
 
         // K-checksum is only appended on barcodes greater than 10 characters.
         if self.0.len() > 10 {
-            let k_checksum = self.k_checksum_char(c_checksum).expect("Cannot compute checksum K");
+            let k_checksum = self.k_checksum_index(c_checksum);
 
-            self.push_encoding(&mut enc, self.char_encoding(k_checksum));
+            self.push_encoding(&mut enc, CHARS[k_checksum].1);
         }
 
         enc
@@ -115,7 +140,7 @@ impl Code11 {
     pub fn encode(&self) -> Vec<u8> {
         let guard = &GUARD[..];
 
-        helpers::join_slices(&[guard, &SEPARATOR,
+        helpers::join_slices(&[guard, &vec![0; self.1][..],
                              &self.payload()[..],
                              guard][..])
     }
@@ -135,9 +160,27 @@ impl Parse for Code11 {
     }
 }
 
+impl Barcode for Code11 {
+    fn encode(&self) -> Vec<u8> {
+        Code11::encode(self)
+    }
+
+    /// The guards, the gaps between the characters and the check characters are overhead.
+    fn density(&self) -> Density {
+        let data = self.0.iter().map(|&i| CHARS[i].1.len()).sum();
+
+        Density::new(self.encode().len(), data, self.metadata().quiet_zone_modules)
+    }
+
+    fn self_check(&self) -> Result<()> {
+        self.check(&self.encode())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use sym::code11::*;
+    use sym::Barcode;
     use error::Error;
     use std::char;
 
@@ -177,4 +220,32 @@ mod tests {
 
         assert_eq!(collapse_vec(code111.encode()), "101100101101011010010110110010101011011010110101101101010011010101001101101001010110101011011011001010100101101101011011011010100110101011001");
     }
+
+    #[test]
+    fn code11_with_separator_width() {
+        let plain = Code11::new("12").unwrap().encode();
+        let encoded = Code11::new("12").unwrap().with_separator_width(2).unwrap().encode();
+
+        // The gaps after the start guard, '1', '2' and the C checksum each grow by a module.
+        assert_eq!(encoded.len(), plain.len() + 4);
+        assert_eq!(encoded[7..9], [0, 0]);
+        assert_eq!(encoded[9..16], plain[8..15]);
+        assert_eq!(encoded[16..18], [0, 0]);
+        assert_eq!(Code11::new("12").unwrap().with_separator_width(2).unwrap().self_check(), Ok(()));
+        assert_eq!(Code11::new("12").unwrap().with_separator_width(0).err().unwrap(), Error::Length);
+    }
+
+    #[test]
+    fn code11_self_check() {
+        let code11 = Code11::new("1234-5678-4321").unwrap();
+        let mut corrupted = code11.encode();
+
+        // Swap the encoding of the '1' for that of a '4' (both 7 modules), as a bad table would.
+        corrupted[8..15].copy_from_slice(CHARS[4].1);
+
+        assert_eq!(code11.self_check(), Ok(()));
+        assert_eq!(Code11::new("123-45").unwrap().self_check(), Ok(()));
+        assert_eq!(code11.check(&corrupted), Err(Error::Generate));
+        assert_eq!(code11.check(&code11.encode()[8..]), Err(Error::Generate));
+    }
 }
@@ -42,6 +42,11 @@
 //!
 //! <ul><li>À\u{0006}</li></ul>
 //!
+//! Only ASCII (`\u{0000}` to `\u{007F}`) can be encoded, along with the special characters
+//! described here. Data is handled `char` by `char`, so anything else (including combining marks
+//! that form part of a larger grapheme) results in `Error::Character`. `Code128::invalid_char`
+//! finds the offending character.
+//!
 //! ## Special-purpose function characters (FNC1 - 4)
 //!
 //! The function sequences can be represented via the following unicode characters:
@@ -51,21 +56,131 @@
 //! - FNC3: ```Ż``` (```\u{017B}```)
 //! - FNC4: ```ż``` (```\u{017C}```)
 //! - SHIFT: ```Ž``` (```\u{017D}```)
+//!
+//! SHIFT encodes the single character after it in the other of character-sets A and B, after
+//! which the current character-set resumes. For example, ```ƁabŽ\u{0006}cd``` encodes the 'ACK'
+//! character without switching out of character-set B.
+//!
+//! ## Escape sequences
+//!
+//! If the Unicode syntax is inconvenient, `Code128::new_with_escape` accepts an escape character
+//! of your choosing instead. For example, with ```~``` as the escape character:
+//!
+//! - ```~A``` (or ```~a```) = Switch to character-set A
+//! - ```~B``` (or ```~b```) = Switch to character-set B
+//! - ```~C``` (or ```~c```) = Switch to character-set C
+//! - ```~~``` = A literal ```~```
+//!
+//! So ```~AHE@$A~C123456``` is equivalent to ```ÀHE@$AĆ123456```.
+//!
+//! ## Forcing the START symbol
+//!
+//! `Code128::new_forcing_start` accepts plain data (without the character-set syntax), begins in
+//! the given character-set and only switches when the data demands it: to reach a character
+//! that is missing from the current set, or to compact a run of four or more digits into
+//! character-set C.
+//!
+//! ## Automatic character-sets
+//!
+//! `Code128::with_strategy` also accepts plain data, choosing the character-sets itself
+//! according to an `OptimizeStrategy`. For example, `AB123456CD` is encoded as `ƁABĆ123456ƁCD`
+//! when minimizing symbols, but as `ƁAB123456CD` when minimizing switches. A lone character from the
+//! other of character-sets A and B is reached with SHIFT (which counts as a switch) when that is
+//! cheaper than switching there and back. As SHIFT is chosen automatically, `Ž` isn't accepted
+//! in the data.
+//!
+//! ## Latin-1
+//!
+//! `Code128::new_latin1` accepts ISO/IEC 8859-1 bytes, encoding those from `0xA0` to `0xFF` by
+//! preceding them with FNC4. Runs of four or more such bytes are instead enclosed in a pair of
+//! double FNC4s, which shifts every character in between. The C1 control codes (`0x80` to `0x9F`)
+//! are not part of Latin-1, and result in `Error::Character`.
+//!
+//! ## GS1
+//!
+//! `Code128::gtin` encodes an even number of digits (such as a GTIN-14 or SSCC) as GS1-128:
+//! START-C, then FNC1, then the digits in pairs.
+//!
+//! ## Units
+//!
+//! For complete control over the symbols, `Code128::from_units` builds a barcode from a sequence
+//! of `Unit`s (symbol values tagged with their character-set) rather than a string.
+//!
+//! ## Builder
+//!
+//! `Code128Builder` assembles a barcode piece by piece, with explicit character-set switches
+//! and FNC1s, which avoids building one large string of special characters. Every piece is
+//! checked against the current character-set as it is added.
+//!
+//! ## Pattern overrides
+//!
+//! `Code128::with_overrides` replaces the bar patterns of individual characters, for printers
+//! that need a bar widened or narrowed. Code39 and Code93 offer the same.
 
-use sym::helpers;
+use sym::{Barcode, Density, helpers};
+use checksum;
 use error::*;
 
-use std::cmp;
+use std::collections::HashMap;
 
+/// A single Code128 symbol: its value (0 to 105) along with the character-set it is encoded
+/// in. See `Code128::from_units`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Unit {
+pub enum Unit {
+    /// A symbol in character-set A.
     A(usize),
+    /// A symbol in character-set B.
     B(usize),
+    /// A symbol in character-set C.
     C(usize),
 }
 
+/// The character-set to begin a barcode in (or switch to). See `Code128::new_forcing_start`
+/// and `Code128Builder`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StartCode {
+    /// START-A (103).
+    A,
+    /// START-B (104).
+    B,
+    /// START-C (105).
+    C,
+}
+
+/// The cost model used when choosing character-sets automatically. See
+/// `Code128::with_strategy`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OptimizeStrategy {
+    /// Minimizes the total number of symbols. Where several encodings are equally short, the
+    /// one with the fewest switches is chosen, and then the one encoding the most data in
+    /// character-set C.
+    MinSymbols,
+    /// Minimizes the number of character-set switches, and then the total number of symbols.
+    MinSwitches,
+}
+
 type Encoding = [u8; 11];
 
+// The shortest run of digits worth switching into character-set C for.
+const MIN_DIGIT_RUN: usize = 4;
+
+// The shortest run of Latin-1 characters worth latching FNC4 for, rather than shifting each.
+const MIN_LATIN1_RUN: usize = 4;
+
+const FNC4: char = '\u{017C}';
+
+// A cost of [symbols, switches, symbols outside of character-set C], along with a position or
+// character-set it was reached from. Used when choosing character-sets automatically.
+type Step = Option<([u32; 3], usize)>;
+
+// An element of the barcode data. Either a character to encode or a switch to
+// another character-set (represented by its Unicode syntax).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token {
+    Switch(char),
+    Char(char),
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum CharacterSet {
     A,
@@ -123,7 +238,7 @@ const CHARS: [([&str; 3], Encoding); 106] = [
     (["\u{0018}", "x", "88"], [1,1,1,1,0,0,1,0,0,1,0]), (["\u{0019}", "y", "89"], [1,1,0,1,1,0,1,1,1,1,0]),
     (["\u{001A}", "z", "90"], [1,1,0,1,1,1,1,0,1,1,0]), (["\u{001B}", "{", "91"], [1,1,1,1,0,1,1,0,1,1,0]),
     (["\u{001C}", "|", "92"], [1,0,1,0,1,1,1,1,0,0,0]), (["\u{001D}", "}", "93"], [1,0,1,0,0,0,1,1,1,1,0]),
-    (["\u{001E}", "~", "94"], [1,0,0,0,1,0,1,1,1,1,0]), (["\u{001F}", "\u{00F7}", "95"], [1,0,1,1,1,1,0,1,0,0,0]),
+    (["\u{001E}", "~", "94"], [1,0,0,0,1,0,1,1,1,1,0]), (["\u{001F}", "\u{007F}", "95"], [1,0,1,1,1,1,0,1,0,0,0]),
     (["\u{017B}", "\u{017B}", "96"], [1,0,1,1,1,1,0,0,0,1,0]), (["\u{017A}", "\u{017A}", "97"], [1,1,1,1,0,1,0,1,0,0,0]),
     (["\u{017D}", "\u{017D}", "98"], [1,1,1,1,0,1,0,0,0,1,0]), (["Ć", "Ć", "99"], [1,0,1,1,1,0,1,1,1,1,0]),
     (["Ɓ", "\u{017C}", "Ɓ"], [1,0,1,1,1,1,0,1,1,1,0]), (["\u{017C}", "À", "À"], [1,1,1,0,1,0,1,1,1,1,0]),
@@ -137,9 +252,23 @@ const STOP: Encoding = [1,1,0,0,0,1,1,1,0,1,0];
 // Termination sequence.
 const TERM: [u8; 2] = [1,1];
 
+// GS1 recommends no more than 48 symbol characters (plus the START and checksum), each of which
+// is 11 modules wide. The STOP and termination bars make up the remaining 13.
+const MAX_MODULES: usize = (50 * 11) + 13;
+
 /// The Code128 barcode type.
 #[derive(Debug)]
-pub struct Code128(Vec<Unit>);
+pub struct Code128(Vec<Unit>, HashMap<(usize, usize), Vec<u8>>);
+
+/// Builds a Code128 barcode incrementally. The first call must be to `switch_set`, which
+/// selects the START symbol.
+#[derive(Clone, Debug)]
+pub struct Code128Builder {
+    tokens: Vec<Token>,
+    char_set: CharacterSet,
+    // Whether a digit in character-set C is waiting for the other digit of its pair.
+    carry: bool,
+}
 
 impl Unit {
     // This seems silly. A better way?
@@ -150,6 +279,15 @@ impl Unit {
             Unit::C(n) => n,
         }
     }
+
+    // Identifies the symbol by its character-set and value, for pattern overrides.
+    fn key(&self) -> (usize, usize) {
+        match *self {
+            Unit::A(n) => (0, n),
+            Unit::B(n) => (1, n),
+            Unit::C(n) => (2, n),
+        }
+    }
 }
 
 impl CharacterSet {
@@ -171,6 +309,18 @@ impl CharacterSet {
         }
     }
 
+    fn switch_char(self) -> char {
+        match self {
+            CharacterSet::A => 'À',
+            CharacterSet::B => 'Ɓ',
+            _ => 'Ć',
+        }
+    }
+
+    fn contains(self, c: char) -> bool {
+        self.lookup(&c.to_string()).is_ok()
+    }
+
     fn index(self) -> Result<usize> {
         match self {
             CharacterSet::A => Ok(0),
@@ -199,28 +349,402 @@ impl Code128 {
             return Err(Error::Length);
         }
 
-        Code128::parse(data.chars().collect()).and_then(|u| {
-            Ok(Code128(u))
-        })
+        Code128::parse(Code128::tokenize(data)).map(Code128::from_parsed)
+    }
+
+    /// Creates a new barcode, using the given escape character to specify the character-sets
+    /// rather than the special Unicode syntax. See the module documentation for details.
+    /// Returns Result<Code128, Error> indicating parse success.
+    pub fn new_with_escape<T: AsRef<str>>(data: T, escape: char) -> Result<Code128> {
+        let data = data.as_ref();
+        if data.len() < 2 { 
+            return Err(Error::Length);
+        }
+
+        Code128::parse(Code128::tokenize_escaped(data, escape)?).map(Code128::from_parsed)
+    }
+
+    /// Creates a new barcode from plain data, beginning in the character-set of the given START
+    /// symbol regardless of the data. See the module documentation for details.
+    /// Returns Result<Code128, Error> indicating parse success.
+    pub fn new_forcing_start<T: AsRef<str>>(data: T, start: StartCode) -> Result<Code128> {
+        let start = match start {
+            StartCode::A => CharacterSet::A,
+            StartCode::B => CharacterSet::B,
+            StartCode::C => CharacterSet::C,
+        };
+
+        Code128::parse(Code128::tokenize_from(data.as_ref(), start)?).map(Code128::from_parsed)
+    }
+
+    /// Creates a new barcode from plain data, choosing the character-sets according to the
+    /// given strategy. See the module documentation for details.
+    /// Returns Result<Code128, Error> indicating parse success.
+    pub fn with_strategy<T: AsRef<str>>(data: T, strategy: OptimizeStrategy) -> Result<Code128> {
+        Code128::parse(Code128::tokenize_optimized(data.as_ref(), strategy)?).map(Code128::from_parsed)
+    }
+
+    /// Creates a new barcode from Latin-1 bytes, beginning in character-set B. See the module
+    /// documentation for details.
+    /// Returns Result<Code128, Error> indicating parse success.
+    pub fn new_latin1(data: &[u8]) -> Result<Code128> {
+        Code128::parse(Code128::tokenize_latin1(data)?).map(Code128::from_parsed)
+    }
+
+    /// Creates a new GS1-128 barcode from an even number of digits (such as a GTIN or SSCC),
+    /// encoded entirely in character-set C after START-C and FNC1. This is always the shortest
+    /// encoding of such data, so the character-set optimizer isn't needed.
+    /// Returns Result<Code128, Error>, failing with `Error::Character` for anything but digits
+    /// and `Error::Length` for no digits or an odd number of them.
+    pub fn gtin<T: AsRef<str>>(digits: T) -> Result<Code128> {
+        let digits = digits.as_ref();
+
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(Error::Character);
+        }
+
+        if digits.is_empty() || digits.len() % 2 == 1 {
+            return Err(Error::Length);
+        }
+
+        let pairs = digits.as_bytes()
+                          .chunks(2)
+                          .map(|p| Unit::C((usize::from(p[0] - b'0') * 10) + usize::from(p[1] - b'0')));
+        let mut units = vec![Unit::C(105), Unit::C(102)];
+
+        units.extend(pairs);
+        Ok(Code128::from_parsed(units))
+    }
+
+    /// Creates a new barcode from the exact sequence of symbols to encode, beginning with a
+    /// START symbol (103, 104 or 105 for character-sets A, B and C respectively). Each unit must
+    /// be in the character-set selected by the preceding START, CODE or SHIFT symbols.
+    /// Returns Result<Code128, Error> indicating parse success.
+    pub fn from_units(units: Vec<Unit>) -> Result<Code128> {
+        let mut char_set = match units.first() {
+            Some(&Unit::A(103)) => CharacterSet::A,
+            Some(&Unit::B(104)) => CharacterSet::B,
+            Some(&Unit::C(105)) => CharacterSet::C,
+            Some(_) => return Err(Error::Character),
+            None => return Err(Error::Length),
+        };
+        let mut shifted = false;
+
+        for &u in &units[1..] {
+            let (set, n) = match u {
+                Unit::A(n) => (CharacterSet::A, n),
+                Unit::B(n) => (CharacterSet::B, n),
+                Unit::C(n) => (CharacterSet::C, n),
+            };
+            // A SHIFT applies to the next symbol only.
+            let expected = match (shifted, char_set) {
+                (true, CharacterSet::A) => CharacterSet::B,
+                (true, CharacterSet::B) => CharacterSet::A,
+                (_, s) => s,
+            };
+
+            if set != expected || n > 102 {
+                return Err(Error::Character);
+            }
+
+            if shifted {
+                shifted = false;
+                continue;
+            }
+
+            match (set, n) {
+                (CharacterSet::A, 98) | (CharacterSet::B, 98) => shifted = true,
+                (CharacterSet::A, 99) | (CharacterSet::B, 99) => char_set = CharacterSet::C,
+                (CharacterSet::A, 100) | (CharacterSet::C, 100) => char_set = CharacterSet::B,
+                (CharacterSet::B, 101) | (CharacterSet::C, 101) => char_set = CharacterSet::A,
+                _ => (),
+            }
+        }
+
+        // A trailing SHIFT has nothing to apply to.
+        if shifted {
+            return Err(Error::Character);
+        }
+
+        Ok(Code128::from_parsed(units))
+    }
+
+    /// Returns the position (in `char`s) and value of the first character in the given data
+    /// that can never be encoded, as it is neither ASCII nor one of the special characters
+    /// described in the module documentation. Useful for diagnosing `Error::Character`.
+    pub fn invalid_char<T: AsRef<str>>(data: T) -> Option<(usize, char)> {
+        data.as_ref()
+            .chars()
+            .enumerate()
+            .find(|&(_, c)| !Code128::is_encodable(c))
+    }
+
+    fn is_encodable(c: char) -> bool {
+        c.is_ascii() || matches!(c, 'À' | 'Ɓ' | 'Ć' | 'Ź' | 'ź' | 'Ż' | 'ż' | 'Ž')
+    }
+
+    fn tokenize(data: &str) -> Vec<Token> {
+        data.chars()
+            .map(|ch| match ch {
+                'À' | 'Ɓ' | 'Ć' => Token::Switch(ch),
+                _ => Token::Char(ch),
+            })
+            .collect()
+    }
+
+    fn tokenize_escaped(data: &str, escape: char) -> Result<Vec<Token>> {
+        let mut tokens = vec![];
+        let mut chars = data.chars();
+
+        while let Some(ch) = chars.next() {
+            let token = if ch == escape {
+                match chars.next() {
+                    Some('A') | Some('a') => Token::Switch('À'),
+                    Some('B') | Some('b') => Token::Switch('Ɓ'),
+                    Some('C') | Some('c') => Token::Switch('Ć'),
+                    Some(c) if c == escape => Token::Char(c),
+                    _ => return Err(Error::Character),
+                }
+            } else {
+                Token::Char(ch)
+            };
+
+            tokens.push(token);
+        }
+
+        Ok(tokens)
+    }
+
+    // Tokenizes plain data starting in the given character-set, switching only where needed.
+    fn tokenize_from(data: &str, start: CharacterSet) -> Result<Vec<Token>> {
+        let chars: Vec<char> = data.chars().collect();
+        let mut tokens = vec![Token::Switch(start.switch_char())];
+        let mut char_set = start;
+        let mut i = 0;
+
+        if chars.is_empty() {
+            return Err(Error::Length);
+        }
+
+        while i < chars.len() {
+            let c = chars[i];
+            let digits = chars[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+
+            if matches!(c, 'À' | 'Ɓ' | 'Ć' | 'Ž') || !Code128::is_encodable(c) {
+                return Err(Error::Character);
+            }
+
+            let next_set = match char_set {
+                CharacterSet::C if digits >= 2 => {
+                    tokens.extend(&[Token::Char(c), Token::Char(chars[i + 1])]);
+                    i += 2;
+                    continue;
+                },
+                // Leave an odd digit behind, so that the rest pair up.
+                _ if digits >= MIN_DIGIT_RUN && digits % 2 == 0 => CharacterSet::C,
+                s if s.contains(c) => {
+                    tokens.push(Token::Char(c));
+                    i += 1;
+                    continue;
+                },
+                _ if CharacterSet::B.contains(c) => CharacterSet::B,
+                _ => CharacterSet::A,
+            };
+
+            tokens.push(Token::Switch(next_set.switch_char()));
+            char_set = next_set;
+        }
+
+        Ok(tokens)
+    }
+
+    // Tokenizes plain data using the cheapest sequence of character-sets, found by tracking the
+    // cheapest way of reaching each position in each character-set. Costs are ranked by the
+    // strategy.
+    fn tokenize_optimized(data: &str, strategy: OptimizeStrategy) -> Result<Vec<Token>> {
+        // Earlier sets win ties, so character-set B is preferred over A.
+        let sets = [CharacterSet::B, CharacterSet::A, CharacterSet::C];
+        let chars: Vec<char> = data.chars().collect();
+        let n = chars.len();
+        let rank = |c: &[u32; 3]| match strategy {
+            OptimizeStrategy::MinSymbols => (c[0], c[1], c[2]),
+            OptimizeStrategy::MinSwitches => (c[1], c[0], c[2]),
+        };
+
+        if n == 0 {
+            return Err(Error::Length);
+        }
+
+        if chars.iter().any(|&c| matches!(c, 'À' | 'Ɓ' | 'Ć' | 'Ž') || !Code128::is_encodable(c)) {
+            return Err(Error::Character);
+        }
+
+        // The cheapest cost of having encoded everything before a position in a set, along
+        // with where the last character began. Then the same, allowing for a switch at the
+        // position itself, along with the set switched from.
+        let mut encoded: Vec<[Step; 3]> = vec![[None; 3]; n + 1];
+        let mut arrived: Vec<[Step; 3]> = vec![[None; 3]; n + 1];
+
+        for e in encoded[0].iter_mut() {
+            *e = Some(([1, 0, 0], 0));
+        }
+
+        for i in 0..=n {
+            for (t, a) in arrived[i].iter_mut().enumerate() {
+                for (s, e) in encoded[i].iter().enumerate() {
+                    if let Some((c, _)) = *e {
+                        let cost = if s == t { c } else { [c[0] + 1, c[1] + 1, c[2]] };
+
+                        if a.map_or(true, |(best, _)| rank(&cost) < rank(&best)) {
+                            *a = Some((cost, s));
+                        }
+                    }
+                }
+            }
+
+            if i == n {
+                break;
+            }
+
+            for (t, &set) in sets.iter().enumerate() {
+                let (len, outside_c) = match set {
+                    CharacterSet::C if i + 1 < n && chars[i].is_ascii_digit() && chars[i + 1].is_ascii_digit() => (2, 0),
+                    CharacterSet::C if set.contains(chars[i]) => (1, 0),
+                    CharacterSet::A | CharacterSet::B if set.contains(chars[i]) => (1, 1),
+                    _ => continue,
+                };
+
+                if let Some((c, _)) = arrived[i][t] {
+                    let cost = [c[0] + 1, c[1], c[2] + outside_c];
+
+                    if encoded[i + len][t].map_or(true, |(best, _)| rank(&cost) < rank(&best)) {
+                        encoded[i + len][t] = Some((cost, i));
+                    }
+                }
+            }
+
+            // A character missing from A or B can be reached with a SHIFT to the other, staying
+            // in the same set afterwards.
+            for (t, &set) in sets.iter().enumerate() {
+                let other = match set {
+                    CharacterSet::A => CharacterSet::B,
+                    CharacterSet::B => CharacterSet::A,
+                    _ => continue,
+                };
+
+                if let (Some((c, _)), false, true) = (arrived[i][t], set.contains(chars[i]), other.contains(chars[i])) {
+                    let cost = [c[0] + 2, c[1] + 1, c[2] + 1];
+
+                    if encoded[i + 1][t].map_or(true, |(best, _)| rank(&cost) < rank(&best)) {
+                        encoded[i + 1][t] = Some((cost, i));
+                    }
+                }
+            }
+        }
+
+        let mut set = (0..sets.len()).filter(|&t| encoded[n][t].is_some())
+                                     .min_by_key(|&t| encoded[n][t].map(|(c, _)| rank(&c)))
+                                     .ok_or(Error::Character)?;
+        let mut tokens = vec![];
+        let mut i = n;
+
+        // Walk back from the end, collecting the tokens in reverse.
+        while i > 0 {
+            let start = encoded[i][set].map_or(0, |(_, start)| start);
+            let previous = arrived[start][set].map_or(set, |(_, s)| s);
+
+            tokens.extend(chars[start..i].iter().rev().map(|&c| Token::Char(c)));
+
+            // Only a SHIFT can have encoded a character that's missing from the set.
+            if sets[set] != CharacterSet::C && !sets[set].contains(chars[start]) {
+                tokens.push(Token::Char('Ž'));
+            }
+
+            if previous != set {
+                tokens.push(Token::Switch(sets[set].switch_char()));
+            }
+
+            set = previous;
+            i = start;
+        }
+
+        tokens.push(Token::Switch(sets[set].switch_char()));
+        tokens.reverse();
+
+        Ok(tokens)
+    }
+
+    // Tokenizes Latin-1 bytes in character-sets A and B, prefixing characters above 0x7F with
+    // the FNC4s that shift them.
+    fn tokenize_latin1(data: &[u8]) -> Result<Vec<Token>> {
+        let mut chars: Vec<(&[char], char)> = vec![];
+        let mut latched = false;
+        let mut i = 0;
+
+        if data.is_empty() {
+            return Err(Error::Length);
+        }
+
+        while i < data.len() {
+            let run = data[i..].iter().take_while(|&&b| b >= 0xA0).count();
+            let prefix: &[char] = match data[i] {
+                0x80..=0x9F => return Err(Error::Character),
+                b if b >= 0xA0 && !latched && run >= MIN_LATIN1_RUN => {
+                    latched = true;
+                    &[FNC4, FNC4]
+                },
+                b if b >= 0xA0 && !latched => &[FNC4],
+                b if b < 0x80 && latched => {
+                    latched = false;
+                    &[FNC4, FNC4]
+                },
+                _ => &[],
+            };
+
+            chars.push((prefix, (data[i] & 0x7F) as char));
+            i += 1;
+        }
+
+        let mut tokens = vec![Token::Switch('Ɓ')];
+        let mut char_set = CharacterSet::B;
+
+        // Any switch must come before the FNC4s, which apply to the very next character.
+        for (prefix, c) in chars {
+            if !char_set.contains(c) {
+                char_set = if CharacterSet::B.contains(c) { CharacterSet::B } else { CharacterSet::A };
+                tokens.push(Token::Switch(char_set.switch_char()));
+            }
+
+            tokens.extend(prefix.iter().map(|&f| Token::Char(f)));
+            tokens.push(Token::Char(c));
+        }
+
+        Ok(tokens)
     }
 
-    // Tokenizes and collects the data into the appropriate character-sets.
-    fn parse(chars: Vec<char>) -> Result<Vec<Unit>> {
+    // Collects the tokens into the appropriate character-sets.
+    fn parse(tokens: Vec<Token>) -> Result<Vec<Unit>> {
         let mut units: Vec<Unit> = vec![];
         let mut char_set = CharacterSet::None;
         let mut carry: Option<char> = None;
+        let mut shifted = false;
+
+        // Catch anything outside of ASCII up front, whatever the character-set.
+        if tokens.iter().any(|&t| matches!(t, Token::Char(c) if !Code128::is_encodable(c))) {
+            return Err(Error::Character);
+        }
 
-        for ch in chars {
-            match ch {
-                'À' | 'Ɓ' | 'Ć' if units.is_empty() => { 
+        for token in tokens {
+            match token {
+                Token::Switch(ch) if units.is_empty() => { 
                     char_set = CharacterSet::from_char(ch)?;
 
                     let c = format!("START-{}", ch);
                     let u = char_set.lookup(&c)?;
                     units.push(u);
                 },
-                'À' | 'Ɓ' | 'Ć' => { 
-                    if char_set == CharacterSet::C && carry.is_some() {
+                Token::Switch(ch) => { 
+                    if (char_set == CharacterSet::C && carry.is_some()) || shifted {
                         return Err(Error::Character);
                     } else {
                         let u = char_set.lookup(&ch.to_string())?;
@@ -229,7 +753,11 @@ impl Code128 {
                         char_set = CharacterSet::from_char(ch)?;
                     }
                 },
-                d if d.is_digit(10) && char_set == CharacterSet::C => {
+                // The switch characters can only be escaped, not encoded literally.
+                Token::Char('À') | Token::Char('Ɓ') | Token::Char('Ć') => {
+                    return Err(Error::Character);
+                },
+                Token::Char(d) if d.is_ascii_digit() && char_set == CharacterSet::C => {
                     match carry {
                         None => carry = Some(d),
                         Some(n) => {
@@ -240,45 +768,137 @@ impl Code128 {
                         }
                     }
                 },
-                _ => {
+                // A lone digit in character-set C cannot be followed by anything but another
+                // digit, otherwise the pair would be encoded out of order.
+                Token::Char(_) if char_set == CharacterSet::C && carry.is_some() => {
+                    return Err(Error::Character);
+                },
+                // A SHIFT applies to the next character only.
+                Token::Char(ch) if shifted => {
+                    let other = match char_set {
+                        CharacterSet::A => CharacterSet::B,
+                        _ => CharacterSet::A,
+                    };
+                    let u = other.lookup(&ch.to_string())?;
+                    units.push(u);
+                    shifted = false;
+                },
+                Token::Char(ch) => {
                     let u = char_set.lookup(&ch.to_string())?;
                     units.push(u);
+                    shifted = matches!(u, Unit::A(98) | Unit::B(98));
                 },
             }
         }
 
+        // As with from_units, a trailing SHIFT has nothing to apply to.
         match carry {
             Some(_) => Err(Error::Character),
+            None if matches!(units.last(), Some(&Unit::A(98)) | Some(&Unit::B(98))) => Err(Error::Character),
+            None if units.is_empty() => Err(Error::Length),
             None => Ok(units)
         }
     }
 
-    /// Calculates the checksum index using a modulo-103 algorithm.
-    fn checksum_value(&self) -> u8 {
-        let sum: i32 = self.0
-                           .iter()
-                           .zip(0..self.0.len() as i32)
-                           .fold(0, |t, (u, i)| t + (u.index() as i32 * cmp::max(1, i)));
+    // Builds a barcode directly from symbol values, for symbologies that are layered on top of
+    // Code128 (eg: Codablock-F). The first value must be a START symbol.
+    pub(crate) fn from_values(values: &[usize]) -> Code128 {
+        Code128::from_parsed(values.iter().map(|&v| Unit::A(v)).collect())
+    }
+
+    fn from_parsed(units: Vec<Unit>) -> Code128 {
+        Code128(units, HashMap::new())
+    }
+
+    /// Returns this barcode with the patterns of the given characters replaced, such as to work
+    /// around a printer that bleeds. Each pattern must be 11 modules wide, like the standard
+    /// ones, and applies to the character in each of the character-sets that contain it. The
+    /// START, STOP and checksum symbols are never overridden.
+    /// Returns Result<Code128, Error>, failing with `Error::Character` for characters that
+    /// are in none of the character-sets (or patterns that aren't binary digits) and
+    /// `Error::Length` for patterns of the wrong width.
+    pub fn with_overrides(self, overrides: &HashMap<char, Vec<u8>>) -> Result<Code128> {
+        let sets = [CharacterSet::A, CharacterSet::B, CharacterSet::C];
+        let overrides = helpers::resolve_overrides(overrides, 11, |c| {
+            sets.iter()
+                .filter_map(|s| s.lookup(&c.to_string()).ok())
+                .map(|u| u.key())
+                .collect()
+        })?;
+
+        Ok(Code128(self.0, overrides))
+    }
+
+    /// Returns the value of the START symbol (103, 104 or 105 for character-sets A, B and C
+    /// respectively). Useful for diagnosing how the data was parsed.
+    pub fn start_code(&self) -> u8 {
+        // Parsing guarantees the first unit is always a START symbol.
+        self.0[0].index() as u8
+    }
+
+    /// Calculates the checksum value using a modulo-103 algorithm. Useful for diagnosing how
+    /// the data was parsed.
+    ///
+    /// Unlike EAN-13 and EAN-8, Code128 has no printable check character: the checksum only
+    /// exists as the encoded symbol before STOP, so there is no `verify` for human-readable
+    /// data. Compare this value against a known one instead.
+    pub fn checksum_value(&self) -> u8 {
+        let values: Vec<usize> = self.0.iter().map(Unit::index).collect();
+
+        checksum::mod103(&values) as u8
+    }
+
+    // Confirms that the given encoding is made up of this barcode's symbols, followed by a
+    // correct checksum and the STOP pattern.
+    fn check(&self, modules: &[u8]) -> Result<()> {
+        let tail = STOP.len() + TERM.len();
+
+        if modules.len() < tail || (modules.len() - tail) % 11 != 0 || modules[modules.len() - tail..] != [&STOP[..], &TERM[..]].concat()[..] {
+            return Err(Error::Generate);
+        }
+
+        let lookup = |c: &[u8]| CHARS.iter().position(|t| t.1[..] == *c).ok_or(Error::Generate);
+        let mut chunks: Vec<&[u8]> = modules[..modules.len() - tail].chunks(11).collect();
+        let checksum = chunks.pop().map(lookup);
+
+        if chunks.len() != self.0.len() {
+            return Err(Error::Generate);
+        }
 
-        (sum % 103) as u8
+        // Overridden symbols can only be compared with their patterns, rather than looked up.
+        let values = self.0
+            .iter()
+            .zip(chunks)
+            .map(|(u, c)| match self.1.get(&u.key()) {
+                Some(o) if o[..] == *c => Ok(u.index()),
+                Some(_) => Err(Error::Generate),
+                None => lookup(c),
+            })
+            .collect::<Result<Vec<usize>>>()?;
+        let expected: Vec<usize> = self.0.iter().map(|u| u.index()).collect();
+
+        if values.is_empty() || values != expected || checksum != Some(Ok(Code128::from_values(&values).checksum_value() as usize)) {
+            return Err(Error::Generate);
+        }
+
+        Ok(())
     }
 
     fn checksum_encoding(&self) -> Encoding {
-        let v = self.checksum_value();
-        self.unit_encoding(&Unit::A(v as usize))
+        CHARS[self.checksum_value() as usize].1
     }
 
-    fn unit_encoding(&self, c: &Unit) -> Encoding {
-       CHARS[c.index()].1
+    fn unit_encoding(&self, c: &Unit) -> &[u8] {
+        self.1.get(&c.key()).map_or(&CHARS[c.index()].1[..], |o| &o[..])
     }
 
     fn payload(&self) -> Vec<u8> {
-        let slices: Vec<Encoding> = self.0
-                                        .iter()
-                                        .map(|u| self.unit_encoding(&u))
-                                        .collect();
+        let slices: Vec<&[u8]> = self.0
+                                     .iter()
+                                     .map(|u| self.unit_encoding(u))
+                                     .collect();
 
-        helpers::join_iters(slices.iter())
+        helpers::join_slices(&slices)
     }
 
     /// Encodes the barcode.
@@ -291,17 +911,300 @@ impl Code128 {
     }
 }
 
+impl Barcode for Code128 {
+    fn encode(&self) -> Vec<u8> {
+        Code128::encode(self)
+    }
+
+    /// The START, checksum and STOP symbols (along with the termination bar) are overhead.
+    fn density(&self) -> Density {
+        Density::new(self.encode().len(), (self.0.len() - 1) * 11, self.metadata().quiet_zone_modules)
+    }
+
+    fn max_modules(&self) -> Option<usize> {
+        Some(MAX_MODULES)
+    }
+
+    fn self_check(&self) -> Result<()> {
+        self.check(&self.encode())
+    }
+}
+
+impl Code128Builder {
+    /// Creates a new, empty builder.
+    pub fn new() -> Code128Builder {
+        Code128Builder {
+            tokens: vec![],
+            char_set: CharacterSet::None,
+            carry: false,
+        }
+    }
+
+    /// Appends the given data in the current character-set. Character-set switches and SHIFT
+    /// can't be given here, while FNC1 to 4 can wherever the character-set has them.
+    /// Returns Result<Code128Builder, Error>, failing with `Error::Character` if any character
+    /// is missing from the current character-set.
+    pub fn push_str<T: AsRef<str>>(mut self, data: T) -> Result<Code128Builder> {
+        for c in data.as_ref().chars() {
+            match (self.char_set, c) {
+                (_, 'À') | (_, 'Ɓ') | (_, 'Ć') | (_, 'Ž') => return Err(Error::Character),
+                (CharacterSet::C, d) if d.is_ascii_digit() => self.carry = !self.carry,
+                (CharacterSet::C, _) if self.carry => return Err(Error::Character),
+                (s, _) if !s.contains(c) => return Err(Error::Character),
+                _ => (),
+            }
+
+            self.tokens.push(Token::Char(c));
+        }
+
+        Ok(self)
+    }
+
+    /// Appends an FNC1, as used by GS1-128.
+    /// Returns Result<Code128Builder, Error>, failing with `Error::Character` if no
+    /// character-set has been selected or a digit in character-set C has no pair.
+    pub fn push_fnc1(self) -> Result<Code128Builder> {
+        self.push_str("Ź")
+    }
+
+    /// Switches to the given character-set, or selects the START symbol if nothing has been
+    /// added yet.
+    /// Returns Result<Code128Builder, Error>, failing with `Error::Character` if a digit in
+    /// character-set C has no pair, or if the given character-set is already selected.
+    pub fn switch_set(mut self, set: StartCode) -> Result<Code128Builder> {
+        let char_set = match set {
+            StartCode::A => CharacterSet::A,
+            StartCode::B => CharacterSet::B,
+            StartCode::C => CharacterSet::C,
+        };
+
+        if self.carry || char_set == self.char_set {
+            return Err(Error::Character);
+        }
+
+        self.char_set = char_set;
+        self.tokens.push(Token::Switch(self.char_set.switch_char()));
+        Ok(self)
+    }
+
+    /// Builds the barcode.
+    /// Returns Result<Code128, Error>, failing with `Error::Length` if no data was added, or
+    /// `Error::Character` if a digit in character-set C has no pair.
+    pub fn build(self) -> Result<Code128> {
+        if self.tokens.iter().all(|t| matches!(t, Token::Switch(_))) {
+            return Err(Error::Length);
+        }
+
+        Code128::parse(self.tokens).map(Code128::from_parsed)
+    }
+}
+
+impl Default for Code128Builder {
+    fn default() -> Code128Builder {
+        Code128Builder::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use sym::code128::*;
+    use sym::Barcode;
     use error::Error;
     use std::char;
+    use std::collections::HashMap;
 
     fn collapse_vec(v: Vec<u8>) -> String {
         let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
         chars.collect()
     }
 
+    #[test]
+    fn non_ascii_code128() {
+        let combining = Code128::new("ƁCafe\u{0301}"); // "e" followed by a combining acute accent.
+        let precomposed = Code128::new("ƁCafé");
+        let emoji = Code128::new("Ć12👍");
+        let escaped = Code128::new_with_escape("~bNa\u{00EF}ve", '~');
+
+        assert_eq!(combining.err().unwrap(), Error::Character);
+        assert_eq!(precomposed.err().unwrap(), Error::Character);
+        assert_eq!(emoji.err().unwrap(), Error::Character);
+        assert_eq!(escaped.err().unwrap(), Error::Character);
+        assert_eq!(Code128::invalid_char("ƁCafe\u{0301}"), Some((5, '\u{0301}')));
+        assert_eq!(Code128::invalid_char("À☺ "), Some((1, '☺')));
+        assert_eq!(Code128::invalid_char("ÀHELLOĆ12Ź34"), None);
+    }
+
+    #[test]
+    fn code128_del() {
+        let code128 = Code128::new("Ɓ\u{007F}").unwrap();
+
+        assert_eq!(code128.0[1], Unit::B(95));
+    }
+
+    #[test]
+    fn code128_forcing_start() {
+        let digits = Code128::new_forcing_start("123456", StartCode::A).unwrap();
+        let mixed = Code128::new_forcing_start("AB12345\u{0006}", StartCode::C).unwrap();
+
+        assert_eq!(digits.start_code(), 103);
+        assert_eq!(digits.0, vec![Unit::A(103), Unit::A(99), Unit::C(12), Unit::C(34), Unit::C(56)]);
+        assert_eq!(digits.encode(), Code128::new("ÀĆ123456").unwrap().encode());
+        assert_eq!(mixed.start_code(), 105);
+        assert_eq!(mixed.encode(), Code128::new("ĆƁAB1Ć2345À\u{0006}").unwrap().encode());
+        assert_eq!(Code128::new_forcing_start("12", StartCode::B).unwrap().encode(),
+                   Code128::new("Ɓ12").unwrap().encode());
+    }
+
+    #[test]
+    fn invalid_code128_forcing_start() {
+        assert_eq!(Code128::new_forcing_start("", StartCode::A).err().unwrap(), Error::Length);
+        assert_eq!(Code128::new_forcing_start("AÀB", StartCode::A).err().unwrap(), Error::Character);
+        assert_eq!(Code128::new_forcing_start("CAFÉ", StartCode::B).err().unwrap(), Error::Character);
+    }
+
+    #[test]
+    fn code128_self_check() {
+        let code128 = Code128::new("ÀHELLOĆ1234").unwrap();
+        let mut corrupted = code128.encode();
+
+        // Swap the encoding of the 'H' for that of an 'I', as a bad table would.
+        corrupted[11..22].copy_from_slice(&CHARS[41].1);
+
+        assert_eq!(code128.self_check(), Ok(()));
+        assert_eq!(code128.check(&corrupted), Err(Error::Generate));
+        assert_eq!(code128.check(&code128.encode()[11..]), Err(Error::Generate));
+    }
+
+    #[test]
+    fn code128_latin1() {
+        let single = Code128::new_latin1(b"caf\xE9!").unwrap();
+        let latched = Code128::new_latin1(b"\xE9\xE9\xE9\xE9A\x06").unwrap();
+
+        assert_eq!(single.0, vec![Unit::B(104), Unit::B(67), Unit::B(65), Unit::B(70), Unit::B(100),
+                                  Unit::B(73), Unit::B(1)]);
+        assert_eq!(latched.0, vec![Unit::B(104), Unit::B(100), Unit::B(100), Unit::B(73), Unit::B(73),
+                                   Unit::B(73), Unit::B(73), Unit::B(100), Unit::B(100), Unit::B(33),
+                                   Unit::B(101), Unit::A(70)]);
+        assert_eq!(single.self_check(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_code128_latin1() {
+        assert_eq!(Code128::new_latin1(b"").err().unwrap(), Error::Length);
+        assert_eq!(Code128::new_latin1(b"caf\x85").err().unwrap(), Error::Character);
+    }
+
+    #[test]
+    fn code128_with_strategy() {
+        let symbols = Code128::with_strategy("AB123456CD", OptimizeStrategy::MinSymbols).unwrap();
+        let switches = Code128::with_strategy("AB123456CD", OptimizeStrategy::MinSwitches).unwrap();
+        let tied = Code128::with_strategy("12AB34", OptimizeStrategy::MinSymbols).unwrap();
+
+        assert_eq!(symbols.0, Code128::new("ƁABĆ123456ƁCD").unwrap().0);
+        assert_eq!(switches.0, Code128::new("ƁAB123456CD").unwrap().0);
+        assert_eq!((symbols.0.len(), switches.0.len()), (10, 11));
+        assert_eq!(symbols.self_check(), Ok(()));
+        assert_eq!(switches.self_check(), Ok(()));
+        // Switching to character-set C and back saves nothing here, so no switch is made.
+        assert_eq!(tied.0, Code128::new("Ɓ12AB34").unwrap().0);
+    }
+
+    #[test]
+    fn code128_with_strategy_mixed() {
+        let data = "AB\u{0006}1234567xyz";
+        let symbols = Code128::with_strategy(data, OptimizeStrategy::MinSymbols).unwrap();
+        let switches = Code128::with_strategy(data, OptimizeStrategy::MinSwitches).unwrap();
+
+        assert_eq!(symbols.0, Code128::new("ÀAB\u{0006}Ć123456Ɓ7xyz").unwrap().0);
+        // A SHIFT is as short as starting in A, and counts as a single switch too.
+        assert_eq!(switches.0, Code128::new("ƁABŽ\u{0006}1234567xyz").unwrap().0);
+        assert_eq!(Code128::with_strategy("ĆŹ12", OptimizeStrategy::MinSymbols).err().unwrap(), Error::Character);
+        assert_eq!(Code128::with_strategy("", OptimizeStrategy::MinSwitches).err().unwrap(), Error::Length);
+    }
+
+    #[test]
+    fn code128_shift() {
+        let shifted = Code128::with_strategy("ab\u{0006}cd", OptimizeStrategy::MinSymbols).unwrap();
+        let parsed = Code128::new("ƁabŽ\u{0006}cd").unwrap();
+        let switched = Code128::with_strategy("ab\u{0006}\u{0006}\u{0006}cd", OptimizeStrategy::MinSymbols).unwrap();
+
+        // A single SHIFT, rather than switching to character-set A and back.
+        assert_eq!(shifted.0, vec![Unit::B(104), Unit::B(65), Unit::B(66), Unit::B(98), Unit::A(70),
+                                   Unit::B(67), Unit::B(68)]);
+        assert_eq!(parsed.0, shifted.0);
+        assert_eq!(shifted.self_check(), Ok(()));
+        // Three characters in a row are cheaper to switch for.
+        assert_eq!(switched.0, Code128::new("ƁabÀ\u{0006}\u{0006}\u{0006}Ɓcd").unwrap().0);
+    }
+
+    #[test]
+    fn invalid_code128_shift() {
+        assert_eq!(Code128::new("ƁaŽÀb").err().unwrap(), Error::Character);
+        assert_eq!(Code128::new("ƁaŽa").err().unwrap(), Error::Character);
+        assert_eq!(Code128::with_strategy("aŽb", OptimizeStrategy::MinSymbols).err().unwrap(), Error::Character);
+        assert_eq!(Code128::new_forcing_start("aŽb", StartCode::B).err().unwrap(), Error::Character);
+    }
+
+    #[test]
+    fn code128_with_overrides() {
+        let pattern = vec![1,1,1,0,1,0,0,0,1,1,0];
+        let overrides: HashMap<char, Vec<u8>> = [('E', pattern.clone())].iter().cloned().collect();
+        let plain = Code128::new("ÀHELLO").unwrap();
+        let code128 = Code128::new("ÀHELLO").unwrap().with_overrides(&overrides).unwrap();
+        let encoded = code128.encode();
+
+        // Only the 'E' (after the START and 'H' symbols) changes, not even the checksum.
+        assert_eq!(encoded[22..33], pattern[..]);
+        assert_eq!(encoded[..22], plain.encode()[..22]);
+        assert_eq!(encoded[33..], plain.encode()[33..]);
+        assert_eq!(code128.self_check(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_code128_overrides() {
+        let unknown: HashMap<char, Vec<u8>> = [('é', vec![1; 11])].iter().cloned().collect();
+        let wide: HashMap<char, Vec<u8>> = [('E', vec![1; 12])].iter().cloned().collect();
+        let code128 = || Code128::new("ÀHELLO").unwrap();
+
+        assert_eq!(code128().with_overrides(&unknown).err().unwrap(), Error::Character);
+        assert_eq!(code128().with_overrides(&wide).err().unwrap(), Error::Length);
+    }
+
+    #[test]
+    fn code128_from_units() {
+        let units = vec![Unit::A(103), Unit::A(40), Unit::A(37), Unit::A(44), Unit::A(44),
+                         Unit::A(47), Unit::A(99), Unit::C(12), Unit::C(34)];
+        let from_units = Code128::from_units(units).unwrap();
+        let from_str = Code128::new("ÀHELLOĆ1234").unwrap();
+
+        assert_eq!(from_units.encode(), from_str.encode());
+        assert_eq!(from_units.start_code(), 103);
+    }
+
+    #[test]
+    fn code128_from_units_shift() {
+        // "a" encoded with a SHIFT to character-set B, followed by "A" back in character-set A.
+        let units = vec![Unit::A(103), Unit::A(98), Unit::B(65), Unit::A(33)];
+
+        assert!(Code128::from_units(units).is_ok());
+    }
+
+    #[test]
+    fn invalid_code128_from_units() {
+        let no_start = Code128::from_units(vec![Unit::A(33), Unit::A(34)]);
+        let wrong_start = Code128::from_units(vec![Unit::B(103), Unit::B(34)]);
+        let wrong_set = Code128::from_units(vec![Unit::A(103), Unit::A(99), Unit::A(12)]);
+        let bad_value = Code128::from_units(vec![Unit::C(105), Unit::C(104)]);
+        let trailing_shift = Code128::from_units(vec![Unit::B(104), Unit::B(98)]);
+
+        assert_eq!(Code128::from_units(vec![]).err().unwrap(), Error::Length);
+        assert_eq!(no_start.err().unwrap(), Error::Character);
+        assert_eq!(wrong_start.err().unwrap(), Error::Character);
+        assert_eq!(wrong_set.err().unwrap(), Error::Character);
+        assert_eq!(bad_value.err().unwrap(), Error::Character);
+        assert_eq!(trailing_shift.err().unwrap(), Error::Character);
+    }
+
     #[test]
     fn new_code128() {
         let code128_a = Code128::new("À !! Ć0201");
@@ -323,10 +1226,12 @@ mod tests {
         let code128_a = Code128::new("À☺ "); // Unknown character.
         let code128_b = Code128::new("ÀHELLOĆ12352"); // Trailing carry at the end.
         let code128_c = Code128::new("HELLO"); // No Character-Set specified.
+        let code128_d = Code128::new("Ć1Ź2"); // FNC1 between the digits of a pair.
 
         assert_eq!(code128_a.err().unwrap(), Error::Character);
         assert_eq!(code128_b.err().unwrap(), Error::Character);
         assert_eq!(code128_c.err().unwrap(), Error::Character);
+        assert_eq!(code128_d.err().unwrap(), Error::Character);
     }
 
     #[test]
@@ -340,6 +1245,40 @@ mod tests {
         assert_eq!(collapse_vec(code128_c.encode()), "1101001000011110010010110110111101110110001011101011110100111001101110010110011100101100110011011001100100010010011100110100101111001100011101011");
     }
 
+    #[test]
+    fn code128_min_height() {
+        let code128 = Code128::new("ÀHELLO").unwrap();
+        let width = code128.encode().len() as u32;
+
+        assert_eq!(width, 90);
+        assert_eq!(code128.min_height(width), 14);
+        assert_eq!(code128.min_height(width * 3), 41);
+    }
+
+    #[test]
+    fn code128_start_code() {
+        let code128_a = Code128::new("ÀHELLO").unwrap();
+        let code128_b = Code128::new("ƁxyZÀ199!*1").unwrap();
+        let code128_c = Code128::new("Ć2199").unwrap();
+
+        assert_eq!(code128_a.start_code(), 103);
+        assert_eq!(code128_b.start_code(), 104);
+        assert_eq!(code128_c.start_code(), 105);
+        assert_eq!(code128_a.0[0], Unit::A(103));
+        assert_eq!(code128_b.0[0], Unit::B(104));
+        assert_eq!(code128_c.0[0], Unit::C(105));
+    }
+
+    #[test]
+    fn code128_checksum_value() {
+        let code128_a = Code128::new("ÀHELLO").unwrap();
+        let code128_b = Code128::new("ÀXYĆ2199").unwrap();
+
+        assert_eq!(code128_a.checksum_value(), 39);
+        assert_eq!(CHARS[code128_a.checksum_value() as usize].1, code128_a.checksum_encoding());
+        assert_eq!(CHARS[code128_b.checksum_value() as usize].1, code128_b.checksum_encoding());
+    }
+
     #[test]
     fn code128_encode_special_chars() {
         let code128_a = Code128::new("ÀB\u{0006}").unwrap();
@@ -354,6 +1293,36 @@ mod tests {
         assert_eq!(collapse_vec(code128_a.encode()), "110100111001111010111010110111000110011100101100010100011001001110110001011101110101111010011101100101011110001100011101011");
     }
  
+    #[test]
+    fn code128_encode_with_escape() {
+        let code128_a = Code128::new("ÀHELLOĆ1234").unwrap();
+        let code128_b = Code128::new_with_escape("~AHELLO~C1234", '~').unwrap();
+        let code128_c = Code128::new_with_escape("\\aHELLO\\c1234", '\\').unwrap();
+        let code128_d = Code128::new("ƁA~BÀ!").unwrap();
+        let code128_e = Code128::new_with_escape("^bA~B^a!", '^').unwrap();
+        let code128_f = Code128::new_with_escape("~bA~~B~a!", '~').unwrap();
+
+        assert_eq!(code128_a.encode(), code128_b.encode());
+        assert_eq!(code128_a.encode(), code128_c.encode());
+        assert_eq!(code128_d.encode(), code128_e.encode());
+        assert_eq!(code128_d.encode(), code128_f.encode());
+    }
+
+    #[test]
+    fn invalid_data_code128_with_escape() {
+        let code128_a = Code128::new_with_escape("~DHELLO", '~'); // Unknown escape.
+        let code128_b = Code128::new_with_escape("~AHELLO~", '~'); // Trailing escape.
+        let code128_c = Code128::new_with_escape("~AHEÀLLO", '~'); // Unicode set switch.
+        let code128_d = Code128::new_with_escape("HELLO", '~'); // No Character-Set specified.
+        let code128_e = Code128::new_with_escape("", '~');
+
+        assert_eq!(code128_a.err().unwrap(), Error::Character);
+        assert_eq!(code128_b.err().unwrap(), Error::Character);
+        assert_eq!(code128_c.err().unwrap(), Error::Character);
+        assert_eq!(code128_d.err().unwrap(), Error::Character);
+        assert_eq!(code128_e.err().unwrap(), Error::Length);
+    }
+
     #[test]
     fn code128_encode_longhand() {
         let code128_a = Code128::new("\u{00C0}HELLO").unwrap();
@@ -364,4 +1333,62 @@ mod tests {
         assert_eq!(collapse_vec(code128_b.encode()), "110100001001110001011011101101000101110111101101110010010111011110100111011001100011101011");
         assert_eq!(collapse_vec(code128_c.encode()), "1101001000011110010010110110111101110110001011101011110100111001101110010110011100101100110011011001100100010010011100110100101111001100011101011");
     }
+
+    #[test]
+    fn code128_builder() {
+        let built = Code128Builder::new().switch_set(StartCode::B)
+                                         .and_then(|b| b.push_str("HEL"))
+                                         .and_then(|b| b.push_str("LO"))
+                                         .and_then(|b| b.switch_set(StartCode::C))
+                                         .and_then(|b| b.push_str("123"))
+                                         .and_then(|b| b.push_str("4"))
+                                         .and_then(|b| b.push_fnc1())
+                                         .and_then(|b| b.push_str("56"))
+                                         .and_then(|b| b.build())
+                                         .unwrap();
+
+        assert_eq!(built.encode(), Code128::new("ƁHELLOĆ1234Ź56").unwrap().encode());
+    }
+
+    #[test]
+    fn invalid_code128_builder() {
+        let c = || Code128Builder::new().switch_set(StartCode::C);
+
+        assert_eq!(Code128Builder::new().push_str("A").err().unwrap(), Error::Character);
+        assert_eq!(Code128Builder::new().push_fnc1().err().unwrap(), Error::Character);
+        assert_eq!(Code128Builder::new().switch_set(StartCode::A).and_then(|b| b.push_str("a")).err().unwrap(), Error::Character);
+        assert_eq!(Code128Builder::new().switch_set(StartCode::B).and_then(|b| b.push_str("AĆ12")).err().unwrap(), Error::Character);
+        assert_eq!(c().and_then(|b| b.push_str("1A")).err().unwrap(), Error::Character);
+        assert_eq!(c().and_then(|b| b.push_str("1")).and_then(|b| b.push_fnc1()).err().unwrap(), Error::Character);
+        assert_eq!(c().and_then(|b| b.push_str("1")).and_then(|b| b.switch_set(StartCode::B)).err().unwrap(), Error::Character);
+        assert_eq!(c().and_then(|b| b.switch_set(StartCode::C)).err().unwrap(), Error::Character);
+        assert_eq!(c().and_then(|b| b.push_str("12")).and_then(|b| b.switch_set(StartCode::B))
+                      .and_then(|b| b.push_str("a")).and_then(|b| b.switch_set(StartCode::B)).err().unwrap(), Error::Character);
+        assert_eq!(c().and_then(|b| b.push_str("123")).and_then(|b| b.build()).err().unwrap(), Error::Character);
+        assert_eq!(c().and_then(|b| b.build()).err().unwrap(), Error::Length);
+        assert_eq!(Code128Builder::new().build().err().unwrap(), Error::Length);
+    }
+
+    #[test]
+    fn code128_gtin() {
+        let sscc = Code128::gtin("001234560000000018").unwrap();
+        let gtin = Code128::gtin("10614141000415").unwrap();
+
+        // START-C, FNC1, one symbol per pair of digits and the checksum, then STOP.
+        assert_eq!(sscc.encode(), Code128::new("ĆŹ001234560000000018").unwrap().encode());
+        assert_eq!(sscc.encode().len(), (12 * 11) + 13);
+        assert_eq!(sscc.start_code(), 105);
+        assert_eq!(gtin.encode(), Code128::new("ĆŹ10614141000415").unwrap().encode());
+        assert_eq!(gtin.encode().len(), (10 * 11) + 13);
+        assert_eq!(gtin.self_check(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_code128_gtin() {
+        assert_eq!(Code128::gtin("1061414100041").err().unwrap(), Error::Length);
+        assert_eq!(Code128::gtin("").err().unwrap(), Error::Length);
+        assert_eq!(Code128::gtin("1061414100041A").err().unwrap(), Error::Character);
+        assert_eq!(Code128::gtin("(01)10614141000415").err().unwrap(), Error::Character);
+        assert_eq!(Code128::gtin("１２").err().unwrap(), Error::Character);
+    }
 }
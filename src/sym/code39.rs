@@ -6,8 +6,10 @@
 //! popular in non-retail environments. It was one of the first symbologies to support encoding
 //! of the ASCII alphabet.
 
-use sym::{Parse, helpers};
-use error::Result;
+use sym::{Barcode, Density, Parse, helpers};
+use checksum;
+use error::{Error, Result};
+use std::collections::HashMap;
 use std::ops::Range;
 
 // Character -> Binary mappings for each of the 43 allowable character.
@@ -32,21 +34,36 @@ const CHARS: [(char, [u8; 12]); 43] = [
 // Code39 barcodes must start and end with the '*' special character.
 const GUARD: [u8; 12] = [1,0,0,1,0,1,1,0,1,1,0,1];
 
+// Most scanners struggle with more than 43 data characters (plus the two guards), each of which
+// is 13 modules wide including the gap.
+const MAX_MODULES: usize = 45 * 13;
+
 /// The Code39 barcode type.
 #[derive(Debug)]
 pub struct Code39 {
-    data: Vec<char>,
+    // Positions of each data character in the CHARS table, resolved up front so that encoding
+    // cannot fail.
+    data: Vec<usize>,
     /// Indicates whether to encode a checksum digit.
     pub checksum: bool,
+    // Patterns replacing those in the CHARS table, keyed by position.
+    overrides: HashMap<usize, Vec<u8>>,
+    // The width of the gap between characters, in modules.
+    separator: usize,
 }
 
 impl Code39 {
     fn init(data: &str, checksum: bool) -> Result<Code39> {
-        Code39::parse(data).and_then(|d| {
-            Ok(Code39 {
-                data: d.chars().collect(),
-                checksum,
-            })
+        let d = Code39::parse(data)?;
+        let indices = d.chars()
+                       .map(|c| CHARS.iter().position(|t| t.0 == c).ok_or(Error::Character))
+                       .collect::<Result<Vec<usize>>>()?;
+
+        Ok(Code39 {
+            data: indices,
+            checksum,
+            overrides: HashMap::new(),
+            separator: 1,
         })
     }
 
@@ -62,44 +79,97 @@ impl Code39 {
         Code39::init(data.as_ref(), true)
     }
 
-    /// Calculates the checksum character using a modulo-43 algorithm.
-    fn checksum_char(&self) -> Option<char> {
-        let get_char_pos = |&c| CHARS.iter().position(|t| t.0 == c).unwrap();
-        let indices = self.data.iter().map(&get_char_pos);
-        let index = indices.sum::<usize>() % CHARS.len();
+    /// Returns this barcode with the patterns of the given characters replaced, such as to work
+    /// around a printer that bleeds. Each pattern must be 12 modules wide, like the standard
+    /// ones. The checksum character uses an overridden pattern too.
+    /// Returns Result<Code39, Error>, failing with `Error::Character` for characters that
+    /// Code39 can't encode (or patterns that aren't binary digits) and `Error::Length` for
+    /// patterns of the wrong width.
+    pub fn with_overrides(self, overrides: &HashMap<char, Vec<u8>>) -> Result<Code39> {
+        let overrides = helpers::resolve_overrides(overrides, 12, |c| {
+            CHARS.iter().position(|t| t.0 == c).into_iter().collect()
+        })?;
+
+        Ok(Code39{overrides, ..self})
+    }
 
-        match CHARS.get(index) {
-            Some(&(c, _)) => Some(c),
-            None => None,
+    /// Returns this barcode with a gap of the given number of modules between characters,
+    /// rather than the standard single module. Some printers need wider gaps, which scanners
+    /// tolerate as Code39 is discrete.
+    /// Returns Result<Code39, Error>, failing with `Error::Length` if the width is zero.
+    pub fn with_separator_width(self, width: usize) -> Result<Code39> {
+        match width {
+            0 => Err(Error::Length),
+            separator => Ok(Code39{separator, ..self}),
         }
     }
 
-    fn checksum_encoding(&self) -> [u8; 12] {
-        match self.checksum_char() {
-            Some(c) => self.char_encoding(c),
-            None => panic!("Cannot compute checksum"),
-        }
+    // Returns the pattern for the character at the given position in the CHARS table.
+    fn char_encoding(&self, index: usize) -> &[u8] {
+        self.overrides.get(&index).map_or(&CHARS[index].1[..], |o| &o[..])
     }
 
-    fn char_encoding(&self, c: char) -> [u8; 12] {
-        match CHARS.iter().find(|&ch| ch.0 == c) {
-            Some(&(_, enc)) => enc,
-            None => panic!(format!("Unknown char: {}", c)),
+    /// Calculates the position of the checksum character using a modulo-43 algorithm.
+    fn checksum_index(&self) -> usize {
+        checksum::mod43(&self.data)
+    }
+
+    // Confirms that the given encoding is made up of this barcode's characters (and checksum),
+    // each followed by a gap, between the two guards.
+    fn check(&self, modules: &[u8]) -> Result<()> {
+        let guards = GUARD.len() * 2;
+        let width = GUARD.len() + self.separator;
+
+        if modules.len() < guards + self.separator || (modules.len() - guards - self.separator) % width != 0 {
+            return Err(Error::Generate);
         }
+
+        let (start, rest) = modules.split_at(GUARD.len());
+        let (payload, end) = rest.split_at(rest.len() - GUARD.len());
+        let (gap, payload) = payload.split_at(self.separator);
+
+        if start != GUARD || end != GUARD || gap.iter().any(|&m| m != 0) {
+            return Err(Error::Generate);
+        }
+
+        let indices = payload
+            .chunks(width)
+            .map(|c| match c.split_at(GUARD.len()) {
+                (encoding, gap) if gap.iter().all(|&m| m == 0) => {
+                    (0..CHARS.len()).find(|&p| self.char_encoding(p) == encoding).ok_or(Error::Generate)
+                },
+                _ => Err(Error::Generate),
+            })
+            .collect::<Result<Vec<usize>>>()?;
+        let mut expected = self.data.clone();
+
+        if self.checksum {
+            expected.push(self.checksum_index());
+        }
+
+        if indices != expected {
+            return Err(Error::Generate);
+        }
+
+        Ok(())
     }
 
-    // Encoded characters are separated by a single "narrow" bar in
-    // Code39 barcodes.
-    fn push_encoding(&self, into: &mut Vec<u8>, from: [u8; 12]) {
+    fn checksum_encoding(&self) -> &[u8] {
+        self.char_encoding(self.checksum_index())
+    }
+
+    // Encoded characters are separated by a "narrow" space in Code39 barcodes, unless a wider
+    // separator was requested.
+    fn push_encoding(&self, into: &mut Vec<u8>, from: &[u8]) {
         into.extend(from.iter().cloned());
-        into.push(0);
+        into.extend(vec![0; self.separator]);
     }
 
     fn payload(&self) -> Vec<u8> {
-        let mut enc = vec![0];
+        let mut enc = vec![0; self.separator];
 
-        for c in &self.data {
-            self.push_encoding(&mut enc, self.char_encoding(*c));
+        for &i in &self.data {
+            self.push_encoding(&mut enc, self.char_encoding(i));
         }
 
         if self.checksum {
@@ -129,11 +199,32 @@ impl Parse for Code39 {
     }
 }
 
+impl Barcode for Code39 {
+    fn encode(&self) -> Vec<u8> {
+        Code39::encode(self)
+    }
+
+    /// The guards, the gaps between the characters and the optional checksum are overhead.
+    fn density(&self) -> Density {
+        Density::new(self.encode().len(), self.data.len() * GUARD.len(), self.metadata().quiet_zone_modules)
+    }
+
+    fn max_modules(&self) -> Option<usize> {
+        Some(MAX_MODULES)
+    }
+
+    fn self_check(&self) -> Result<()> {
+        self.check(&self.encode())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use sym::code39::*;
-    use error::Error;
+    use sym::Barcode;
+    use error::{Error, Warning};
     use std::char;
+    use std::collections::HashMap;
 
     fn collapse_vec(v: Vec<u8>) -> String {
         let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
@@ -161,6 +252,15 @@ mod tests {
         assert_eq!(code39.err().unwrap(), Error::Length);
     }
 
+    #[test]
+    fn code39_capacity() {
+        let code391 = Code39::new("TEST8052").unwrap();
+        let code392 = Code39::new("THIS IS A RATHER LONG PIECE OF DATA FOR CODE39").unwrap();
+
+        assert!(code391.validate_capacity().is_empty());
+        assert_eq!(code392.validate_capacity(), vec![Warning::Capacity{modules: 623, recommended: 585}]);
+    }
+
     #[test]
     fn code39_encode() {
         let code391 = Code39::new("1234").unwrap();
@@ -172,6 +272,79 @@ mod tests {
         assert_eq!(collapse_vec(code393.encode()), "100101101101010101101100101101011001010101101011001010101101100101101001011010101001101101011010011010101011001010110100101101101");
     }
 
+    #[test]
+    fn code39_printed_width() {
+        let code39 = Code39::new("1234").unwrap();
+
+        // Two guards and four characters, each 12 modules, with a gap after all but the last.
+        assert_eq!(code39.printed_width(0.25, false), 77.0 * 0.25);
+        assert_eq!(code39.printed_width(0.25, true), 97.0 * 0.25);
+        assert_eq!(Code39::with_checksum("1234").unwrap().printed_width(0.25, false), 90.0 * 0.25);
+    }
+
+    #[test]
+    fn code39_encode_published() {
+        let code39 = Code39::new("CODE 39").unwrap();
+
+        assert_eq!(collapse_vec(code39.encode()), "10010110110101101101001010110101101001010101100101101101011001010100110101101011011001010101011001011010100101101101");
+    }
+
+    #[test]
+    fn code39_chars_well_formed() {
+        // Each character is 5 bars and 4 spaces, 3 of which are wide.
+        for &(c, enc) in CHARS.iter().chain([('*', GUARD)].iter()) {
+            let elements = enc.windows(2).filter(|w| w[0] != w[1]).count() + 1;
+            let wide = enc.windows(2).filter(|w| w[0] == w[1]).count();
+
+            assert_eq!((enc[0], enc[11]), (1, 1), "{}", c);
+            assert_eq!(elements, 9, "{}", c);
+            assert_eq!(wide, 3, "{}", c);
+        }
+    }
+
+    #[test]
+    fn code39_with_overrides() {
+        let wide_a = vec![1,1,1,0,1,0,0,1,0,1,1,1];
+        let overrides: HashMap<char, Vec<u8>> = [('A', wide_a.clone())].iter().cloned().collect();
+        let plain = Code39::new("BAB").unwrap().encode();
+        let code39 = Code39::new("BAB").unwrap().with_overrides(&overrides).unwrap();
+        let encoded = code39.encode();
+
+        // Only the 'A' (between the start guard and 'B', each 13 modules with its gap) changes.
+        assert_eq!(encoded[26..38], wide_a[..]);
+        assert_eq!(encoded[..26], plain[..26]);
+        assert_eq!(encoded[38..], plain[38..]);
+        assert_eq!(code39.self_check(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_code39_overrides() {
+        let unknown: HashMap<char, Vec<u8>> = [('a', vec![1; 12])].iter().cloned().collect();
+        let narrow: HashMap<char, Vec<u8>> = [('A', vec![1; 11])].iter().cloned().collect();
+        let not_binary: HashMap<char, Vec<u8>> = [('A', vec![2; 12])].iter().cloned().collect();
+        let code39 = || Code39::new("BAB").unwrap();
+
+        assert_eq!(code39().with_overrides(&unknown).err().unwrap(), Error::Character);
+        assert_eq!(code39().with_overrides(&narrow).err().unwrap(), Error::Length);
+        assert_eq!(code39().with_overrides(&not_binary).err().unwrap(), Error::Character);
+    }
+
+    #[test]
+    fn code39_with_separator_width() {
+        let plain = Code39::new("AB").unwrap().encode();
+        let code39 = Code39::new("AB").unwrap().with_separator_width(2).unwrap();
+        let encoded = code39.encode();
+
+        // Each of the three gaps (after the start guard, 'A' and 'B') grows by a module.
+        assert_eq!(encoded.len(), plain.len() + 3);
+        assert_eq!(encoded[12..14], [0, 0]);
+        assert_eq!(encoded[14..26], plain[13..25]);
+        assert_eq!(encoded[26..28], [0, 0]);
+        assert_eq!(encoded[28], 1);
+        assert_eq!(code39.self_check(), Ok(()));
+        assert_eq!(Code39::new("AB").unwrap().with_separator_width(0).err().unwrap(), Error::Length);
+    }
+
     #[test]
     fn code39_encode_with_checksum() {
         let code391 = Code39::with_checksum("1234").unwrap();
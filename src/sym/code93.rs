@@ -8,8 +8,10 @@
 //! NOTE: This encoder currently only supports the basic Code93 implementation and not full-ASCII
 //! mode.
 
-use sym::{Parse, helpers};
-use error::Result;
+use sym::{Barcode, Density, Parse, helpers};
+use checksum;
+use error::{Error, Result};
+use std::collections::HashMap;
 use std::ops::Range;
 
 // Character -> Binary mappings for each of the 47 allowable character.
@@ -30,78 +32,117 @@ const CHARS: [(char, [u8; 9]); 47] = [
     ('-', [1,0,0,1,0,1,1,1,0]), ('.', [1,1,1,0,1,0,1,0,0]), (' ', [1,1,1,0,1,0,0,1,0]),
     ('$', [1,1,1,0,0,1,0,1,0]), ('/', [1,0,1,1,0,1,1,1,0]), ('+', [1,0,1,1,1,0,1,1,0]),
     ('%', [1,1,0,1,0,1,1,1,0]), ('(', [1,0,0,1,0,0,1,1,0]), (')', [1,1,1,0,1,1,0,1,0]),
-    ('[', [1,1,1,0,1,0,1,1,0]), ('[', [1,0,0,1,1,0,0,1,0]),
+    ('[', [1,1,1,0,1,0,1,1,0]), (']', [1,0,0,1,1,0,0,1,0]),
 ];
 
 // Code93 barcodes must start and end with the '*' special character.
 const GUARD: [u8; 9] = [1,0,1,0,1,1,1,1,0];
 const TERMINATOR: [u8; 1] = [1];
 
+// Most scanners struggle with more than 48 data characters (plus the two checksums and two guards),
+// each of which is 9 modules wide.
+const MAX_MODULES: usize = (52 * 9) + 1;
+
 /// The Code93 barcode type.
 #[derive(Debug)]
-pub struct Code93(Vec<char>);
+pub struct Code93(Vec<usize>, HashMap<usize, Vec<u8>>);
 
 impl Code93 {
     /// Creates a new barcode.
     /// Returns Result<Code93, Error> indicating parse success.
     pub fn new<T: AsRef<str>>(data: T) -> Result<Code93> {
-        Code93::parse(data.as_ref()).and_then(|d| {
-            Ok(Code93(d.chars()
-                       .collect()))
-        })
+        let d = Code93::parse(data.as_ref())?;
+
+        // Characters are stored as their position in the CHARS table so that encoding cannot
+        // fail.
+        d.chars()
+         .map(|c| CHARS.iter().position(|t| t.0 == c).ok_or(Error::Character))
+         .collect::<Result<Vec<usize>>>()
+         .map(|d| Code93(d, HashMap::new()))
     }
 
-    fn char_encoding(&self, c: char) -> [u8; 9] {
-        match CHARS.iter().find(|&ch| ch.0 == c) {
-            Some(&(_, enc)) => enc,
-            None => panic!(format!("Unknown char: {}", c)),
-        }
+    /// Returns this barcode with the patterns of the given characters replaced, such as to work
+    /// around a printer that bleeds. Each pattern must be 9 modules wide, like the standard
+    /// ones. The checksum characters use overridden patterns too.
+    /// Returns Result<Code93, Error>, failing with `Error::Character` for characters that
+    /// Code93 can't encode (or patterns that aren't binary digits) and `Error::Length` for
+    /// patterns of the wrong width.
+    pub fn with_overrides(self, overrides: &HashMap<char, Vec<u8>>) -> Result<Code93> {
+        let overrides = helpers::resolve_overrides(overrides, 9, |c| {
+            CHARS.iter().position(|t| t.0 == c).into_iter().collect()
+        })?;
+
+        Ok(Code93(self.0, overrides))
     }
 
-    /// Calculates a checksum character using a weighted modulo-47 algorithm.
-    fn checksum_char(&self, data: &[char], weight_threshold: usize) -> Option<char> {
-        let get_char_pos = |&c| CHARS.iter().position(|t| t.0 == c).unwrap();
-        let weight = |i| {
-            match (data.len() - i) % weight_threshold {
-                0 => weight_threshold,
-                n => n,
-            }
-        };
-        let positions = data.iter().map(&get_char_pos);
-        let index = positions.enumerate()
-                             .fold(0, |acc, (i, pos)| acc + (weight(i) * pos));
-
-        match CHARS.get(index % CHARS.len()) {
-            Some(&(c, _)) => Some(c),
-            None => None,
-        }
+    // Returns the pattern for the character at the given position in the CHARS table.
+    fn char_encoding(&self, index: usize) -> &[u8] {
+        self.1.get(&index).map_or(&CHARS[index].1[..], |o| &o[..])
     }
 
+    /// Calculates the position of a checksum character using a weighted modulo-47 algorithm.
+    fn checksum_index(&self, data: &[usize], weight_threshold: usize) -> usize {
+        checksum::mod47(data, weight_threshold)
+    }
 
-    /// Calculates the C checksum character using a weighted modulo-47 algorithm.
-    fn c_checksum_char(&self) -> Option<char> {
-        self.checksum_char(&self.0, 20)
+    /// Calculates the position of the C checksum character.
+    fn c_checksum_index(&self) -> usize {
+        self.checksum_index(&self.0, 20)
     }
 
-    /// Calculates the K checksum character using a weighted modulo-47 algorithm.
-    fn k_checksum_char(&self, c_checksum: char) -> Option<char> {
-        let mut data: Vec<char> = self.0.clone();
+    /// Calculates the position of the K checksum character.
+    fn k_checksum_index(&self, c_checksum: usize) -> usize {
+        let mut data: Vec<usize> = self.0.clone();
         data.push(c_checksum);
 
-        self.checksum_char(&data, 15)
+        self.checksum_index(&data, 15)
+    }
+
+    // Confirms that the given encoding is made up of this barcode's characters and checksums,
+    // between the two guards and followed by the termination bar.
+    fn check(&self, modules: &[u8]) -> Result<()> {
+        let overhead = (GUARD.len() * 2) + TERMINATOR.len();
+
+        if modules.len() < overhead || (modules.len() - overhead) % GUARD.len() != 0 {
+            return Err(Error::Generate);
+        }
+
+        let (start, rest) = modules.split_at(GUARD.len());
+        let (payload, end) = rest.split_at(rest.len() - GUARD.len() - TERMINATOR.len());
+        let (end, terminator) = end.split_at(GUARD.len());
+
+        if start != GUARD || end != GUARD || terminator != TERMINATOR {
+            return Err(Error::Generate);
+        }
+
+        let indices = payload
+            .chunks(GUARD.len())
+            .map(|c| (0..CHARS.len()).find(|&p| self.char_encoding(p) == c).ok_or(Error::Generate))
+            .collect::<Result<Vec<usize>>>()?;
+        let c_checksum = self.c_checksum_index();
+        let mut expected = self.0.clone();
+
+        expected.push(c_checksum);
+        expected.push(self.k_checksum_index(c_checksum));
+
+        if indices != expected {
+            return Err(Error::Generate);
+        }
+
+        Ok(())
     }
 
-    fn push_encoding(&self, into: &mut Vec<u8>, from: [u8; 9]) {
+    fn push_encoding(&self, into: &mut Vec<u8>, from: &[u8]) {
         into.extend(from.iter().cloned());
     }
 
     fn payload(&self) -> Vec<u8> {
         let mut enc = vec![];
-        let c_checksum = self.c_checksum_char().expect("Cannot compute checksum C");
-        let k_checksum = self.k_checksum_char(c_checksum).expect("Cannot compute checksum K");
+        let c_checksum = self.c_checksum_index();
+        let k_checksum = self.k_checksum_index(c_checksum);
 
-        for &c in &self.0 {
-            self.push_encoding(&mut enc, self.char_encoding(c));
+        for &i in &self.0 {
+            self.push_encoding(&mut enc, self.char_encoding(i));
         }
 
         // Checksums.
@@ -136,11 +177,32 @@ impl Parse for Code93 {
     }
 }
 
+impl Barcode for Code93 {
+    fn encode(&self) -> Vec<u8> {
+        Code93::encode(self)
+    }
+
+    /// The guards, the two check characters and the termination bar are overhead.
+    fn density(&self) -> Density {
+        Density::new(self.encode().len(), self.0.len() * GUARD.len(), self.metadata().quiet_zone_modules)
+    }
+
+    fn max_modules(&self) -> Option<usize> {
+        Some(MAX_MODULES)
+    }
+
+    fn self_check(&self) -> Result<()> {
+        self.check(&self.encode())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use sym::code93::*;
+    use sym::Barcode;
     use error::Error;
     use std::char;
+    use std::collections::HashMap;
 
     fn collapse_vec(v: Vec<u8>) -> String {
         let chars = v.iter().map(|d| char::from_digit(*d as u32, 10).unwrap());
@@ -174,4 +236,32 @@ mod tests {
         assert_eq!(collapse_vec(code933.encode()), "1010111101000010101000010101101100101000101101010111101");
         assert_eq!(collapse_vec(code934.encode()), "1010111101010010001010010001010010001010010001010010001010010001010010001010010001010010001010010001010010001010010001010010001010010001010010001010010001010010001010010001010010001010010001010010001010010001000101101110010101010111101");
     }
+
+    #[test]
+    fn code93_with_overrides() {
+        let pattern = vec![1,1,0,0,1,0,1,0,0];
+        let overrides: HashMap<char, Vec<u8>> = [('L', pattern.clone())].iter().cloned().collect();
+        let plain = Code93::new("FLAM").unwrap().encode();
+        let encoded = Code93::new("FLAM").unwrap().with_overrides(&overrides).unwrap().encode();
+
+        // Only the 'L' (after the start guard and 'F', each 9 modules) changes.
+        assert_eq!(encoded[18..27], pattern[..]);
+        assert_eq!(encoded[..18], plain[..18]);
+        assert_eq!(encoded[27..], plain[27..]);
+        assert_eq!(Code93::new("FLAM").unwrap().with_overrides(&[('l', pattern)].iter().cloned().collect()).err().unwrap(), Error::Character);
+        assert_eq!(Code93::new("FLAM").unwrap().with_overrides(&[('L', vec![1; 11])].iter().cloned().collect()).err().unwrap(), Error::Length);
+    }
+
+    #[test]
+    fn code93_self_check() {
+        let code93 = Code93::new("TEST93").unwrap();
+        let mut corrupted = code93.encode();
+
+        // Swap the encoding of the 'E' for that of an 'F', as a bad table would.
+        corrupted[18..27].copy_from_slice(&CHARS[15].1);
+
+        assert_eq!(code93.self_check(), Ok(()));
+        assert_eq!(code93.check(&corrupted), Err(Error::Generate));
+        assert_eq!(code93.check(&code93.encode()[9..]), Err(Error::Generate));
+    }
 }
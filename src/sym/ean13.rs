@@ -8,11 +8,14 @@
 //!   * EAN-13
 //!   * Bookland
 //!   * JAN
+//!
+//! Data may be given with or without the check digit. If it is given, it will be verified.
 
-use sym::{Parse, helpers};
-use error::Result;
+use sym::{Barcode, Density, Parse, SymbologyMeta, helpers};
+use error::{Error, Result};
 use std::ops::Range;
 use std::char;
+use std::fmt;
 
 /// Encoding mappings for EAN barcodes.
 /// 1 = bar, 0 = no bar.
@@ -46,6 +49,10 @@ pub const MIDDLE_GUARD: [u8; 5] = [0, 1, 0, 1, 0];
 /// The right-hand guard pattern.
 pub const RIGHT_GUARD: [u8; 3] = [1, 0, 1];
 
+// Guards (3 + 5 + 3) and 12 digits of 7 modules each, as the first digit is implied by the parity
+// of the left-hand digits.
+const ENCODED_LEN: usize = 11 + (12 * 7);
+
 /// The EAN-13 barcode type.
 #[derive(Debug)]
 pub struct EAN13(Vec<u8>);
@@ -63,15 +70,99 @@ pub type UPCA = EAN13;
 pub type JAN = EAN13;
 
 impl EAN13 {
-    /// Creates a new barcode.
+    /// Creates a new barcode from 12 digits, or 13 digits including the check digit.
     /// Returns Result<EAN13, Error> indicating parse success.
     pub fn new<T: AsRef<str>>(data: T) -> Result<EAN13> {
-        EAN13::parse(data.as_ref()).and_then(|d| {
-            let digits = d.chars()
-                          .map(|c| c.to_digit(10).expect("Unknown character") as u8)
-                          .collect();
-            Ok(EAN13(digits))
-        })
+        let d = EAN13::parse(data.as_ref())?;
+        let mut digits = d.chars()
+                          .map(|c| c.to_digit(10).map(|d| d as u8).ok_or(Error::Character))
+                          .collect::<Result<Vec<u8>>>()?;
+
+        if digits.len() == 13 {
+            let check_digit = digits.pop();
+
+            if check_digit != Some(helpers::modulo_10_checksum(&digits[..], true)) {
+                return Err(Error::Checksum);
+            }
+        }
+
+        Ok(EAN13(digits))
+    }
+
+    /// Checks whether the last digit of the given 13-digit code is its correct check digit,
+    /// without encoding it.
+    /// Returns Result<bool, Error>, failing if the code is not made up of 13 digits.
+    pub fn verify<T: AsRef<str>>(full_code: T) -> Result<bool> {
+        let d = EAN13::parse(full_code.as_ref())?;
+
+        if d.len() != 13 {
+            return Err(Error::Length);
+        }
+
+        match EAN13::new(d) {
+            Ok(_) => Ok(true),
+            Err(Error::Checksum) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Decodes the given binary digits (as returned by `encode`) back into the 13 digits of
+    /// barcode data, including the check digit.
+    /// Returns Result<String, Error> indicating decode success.
+    pub fn decode(modules: &[u8]) -> Result<String> {
+        if modules.len() != ENCODED_LEN {
+            return Err(Error::Length);
+        }
+
+        if modules[0..3] != LEFT_GUARD || modules[45..50] != MIDDLE_GUARD || modules[92..] != RIGHT_GUARD {
+            return Err(Error::Character);
+        }
+
+        let left = modules[3..45].chunks(7)
+                                 .map(EAN13::decode_left_digit)
+                                 .collect::<Result<Vec<(usize, u8)>>>()?;
+        let right = modules[50..92].chunks(7)
+                                   .map(|c| EAN13::decode_digit(2, c))
+                                   .collect::<Result<Vec<u8>>>()?;
+        let parity: Vec<usize> = left[1..].iter().map(|&(p, _)| p).collect();
+        let first = match PARITY.iter().position(|p| p[..] == parity[..]) {
+            Some(d) if left[0].0 == 0 => d as u8,
+            _ => return Err(Error::Character),
+        };
+
+        let mut digits = vec![first];
+        digits.extend(left.iter().map(|&(_, d)| d));
+        digits.extend(right);
+
+        let check_digit = digits.pop();
+
+        if check_digit != Some(helpers::modulo_10_checksum(&digits[..], true)) {
+            return Err(Error::Checksum);
+        }
+
+        Ok(EAN13(digits).to_string())
+    }
+
+    // Returns the parity (0 for odd, 1 for even) and value of a left-hand digit.
+    fn decode_left_digit(encoding: &[u8]) -> Result<(usize, u8)> {
+        EAN13::decode_digit(0, encoding)
+            .map(|d| (0, d))
+            .or_else(|_| EAN13::decode_digit(1, encoding).map(|d| (1, d)))
+    }
+
+    fn decode_digit(side: usize, encoding: &[u8]) -> Result<u8> {
+        match ENCODINGS[side].iter().position(|e| e[..] == *encoding) {
+            Some(d) => Ok(d as u8),
+            None => Err(Error::Character),
+        }
+    }
+
+    // Confirms that the given encoding decodes back to this barcode's data.
+    fn check(&self, modules: &[u8]) -> Result<()> {
+        match EAN13::decode(modules) {
+            Ok(ref d) if *d == self.to_string() => Ok(()),
+            _ => Err(Error::Generate),
+        }
     }
 
     /// Calculates the checksum digit using a modulo-10 weighting algorithm.
@@ -139,6 +230,17 @@ impl EAN13 {
     }
 }
 
+impl fmt::Display for EAN13 {
+    /// Formats the barcode data, including the check digit.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for d in self.0.iter().chain(Some(self.checksum_digit()).iter()) {
+            write!(f, "{}", d)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl Parse for EAN13 {
     /// Returns the valid length of data acceptable in this type of barcode.
     fn valid_len() -> Range<u32> {
@@ -151,9 +253,50 @@ impl Parse for EAN13 {
     }
 }
 
+impl Barcode for EAN13 {
+    fn encode(&self) -> Vec<u8> {
+        EAN13::encode(self)
+    }
+
+    /// EAN-13 specifies a nominal bar height of 22.85mm for a 31.35mm wide symbol.
+    fn min_height(&self, width_px: u32) -> u32 {
+        helpers::height_from_ratio(width_px, 73)
+    }
+
+    fn guard_ranges(&self) -> Vec<Range<usize>> {
+        vec![0..3, 45..50, 92..95]
+    }
+
+    /// The first digit is carried by the parity of the left half, so only the others have
+    /// modules of their own. The guards and check digit are overhead.
+    fn density(&self) -> Density {
+        Density::new(self.encode().len(), (self.0.len() - 1) * 7, self.metadata().quiet_zone_modules)
+    }
+
+    fn metadata(&self) -> SymbologyMeta {
+        SymbologyMeta::new(&self.guard_ranges(), 9)
+    }
+
+    fn self_check(&self) -> Result<()> {
+        self.check(&self.encode())
+    }
+
+    /// The first digit sits beneath the left guard, followed by a group of six digits on each
+    /// side of the middle guard.
+    fn human_readable(&self) -> Vec<(Range<usize>, String)> {
+        let digits = |d: &[u8]| d.iter().map(|d| d.to_string()).collect::<String>();
+        let right = [&self.0[7..], &[self.checksum_digit()][..]].concat();
+
+        vec![(0..3, digits(&self.0[..1])),
+             (3..45, digits(&self.0[1..7])),
+             (50..92, digits(&right))]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use ::sym::ean13::*;
+    use ::sym::Barcode;
     use std::char;
     use error::Error;
 
@@ -183,6 +326,69 @@ mod tests {
         assert_eq!(ean13.err().unwrap(), Error::Character)
     }
 
+    #[test]
+    fn ean13_human_readable() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let encoded = ean13.encode();
+
+        assert_eq!(ean13.human_readable(), vec![(0..3, "7".to_string()),
+                                                (3..45, "501031".to_string()),
+                                                (50..92, "311309".to_string())]);
+        assert!(ean13.guard_ranges().iter().all(|r| encoded[r.start] == encoded[r.end - 1]));
+    }
+
+    #[test]
+    fn ean13_metadata() {
+        let meta = EAN13::new("750103131130").unwrap().metadata();
+
+        assert_eq!(meta.guard_positions, vec![0, 1, 2, 45, 46, 47, 48, 49, 92, 93, 94]);
+        assert_eq!(meta.quiet_zone_modules, 9);
+        assert!(!meta.uses_height_modulation);
+    }
+
+    #[test]
+    fn ean13_printed_width() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+
+        // 95 modules, plus a quiet zone of 9 on each side.
+        assert_eq!(ean13.printed_width(0.33, false), 95.0 * 0.33);
+        assert_eq!(ean13.printed_width(0.33, true), 113.0 * 0.33);
+    }
+
+    #[test]
+    fn ean13_decode() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+        let mut corrupted = ean13.encode();
+
+        assert_eq!(EAN13::decode(&ean13.encode()), Ok("7501031311309".to_string()));
+        assert_eq!(EAN13::decode(&ean13.encode()[1..]), Err(Error::Length));
+
+        // Swap the encoding of the second digit ('5') for that of a '6', as a bad table would.
+        corrupted[3..10].copy_from_slice(&ENCODINGS[0][6]);
+
+        assert_eq!(EAN13::decode(&corrupted), Err(Error::Checksum));
+        assert_eq!(ean13.check(&corrupted), Err(Error::Generate));
+        assert_eq!(ean13.self_check(), Ok(()));
+    }
+
+    #[test]
+    fn new_ean13_with_check_digit() {
+        let ean13 = EAN13::new("7501031311309");
+
+        assert!(ean13.is_ok());
+        assert_eq!(ean13.unwrap().encode(), EAN13::new("750103131130").unwrap().encode());
+        assert_eq!(EAN13::new("7501031311308").err().unwrap(), Error::Checksum);
+    }
+
+    #[test]
+    fn ean13_verify() {
+        assert_eq!(EAN13::verify("7501031311309"), Ok(true));
+        assert_eq!(EAN13::verify("7501031311308"), Ok(false));
+        assert_eq!(EAN13::verify("7501031311390"), Ok(false));
+        assert_eq!(EAN13::verify("750103131130"), Err(Error::Length));
+        assert_eq!(EAN13::verify("750103131130A"), Err(Error::Character));
+    }
+
     #[test]
     fn invalid_len_ean13() {
         let ean13 = EAN13::new("1111112222222333333");
@@ -216,4 +422,13 @@ mod tests {
         assert_eq!(collapse_vec(ean131.encode()), "10101100010100111001100101001110111101011001101010100001011001101100110100001011100101110100101");
         assert_eq!(collapse_vec(ean132.encode()), "10101101110100001001110101011110111001001100101010110110010000101011100111010011101001000010101");
     }
+
+    #[test]
+    fn ean13_min_height() {
+        let ean13 = EAN13::new("750103131130").unwrap();
+
+        assert_eq!(ean13.min_height(95), 70);
+        assert_eq!(ean13.min_height(190), 139);
+        assert_eq!(ean13.min_height(0), 0);
+    }
 }
@@ -2,30 +2,100 @@
 //!
 //! EAN-8 barcodes are EAN style barcodes for smaller packages on products like
 //! cigaretts, chewing gum, etc where package space is limited.
+//!
+//! Data may be given with or without the check digit. If it is given, it will be verified.
 
-use sym::{Parse, helpers};
-use error::Result;
+use sym::{Barcode, Density, Parse, SymbologyMeta, helpers};
+use error::{Error, Result};
 use sym::ean13::{ENCODINGS,
                  LEFT_GUARD,
                  MIDDLE_GUARD,
                  RIGHT_GUARD};
 use std::ops::Range;
 use std::char;
+use std::fmt;
+
+// Guards (3 + 5 + 3) and 8 digits of 7 modules each.
+const ENCODED_LEN: usize = 11 + (8 * 7);
 
 /// The EAN-8 barcode type.
 #[derive(Debug)]
 pub struct EAN8(Vec<u8>);
 
 impl EAN8 {
-    /// Creates a new barcode.
-    /// Returns Result<EAN8, String> indicating parse success.
+    /// Creates a new barcode from 7 digits, or 8 digits including the check digit.
+    /// Returns Result<EAN8, Error> indicating parse success.
     pub fn new<T: AsRef<str>>(data: T) -> Result<EAN8> {
-        EAN8::parse(data.as_ref()).and_then(|d| {
-            let digits = d.chars()
-                          .map(|c| c.to_digit(10).expect("Unknown character") as u8)
-                          .collect();
-            Ok(EAN8(digits))
-        })
+        let d = EAN8::parse(data.as_ref())?;
+        let mut digits = d.chars()
+                          .map(|c| c.to_digit(10).map(|d| d as u8).ok_or(Error::Character))
+                          .collect::<Result<Vec<u8>>>()?;
+
+        if digits.len() == 8 {
+            let check_digit = digits.pop();
+
+            if check_digit != Some(helpers::modulo_10_checksum(&digits[..], false)) {
+                return Err(Error::Checksum);
+            }
+        }
+
+        Ok(EAN8(digits))
+    }
+
+    /// Checks whether the last digit of the given 8-digit code is its correct check digit,
+    /// without encoding it.
+    /// Returns Result<bool, Error>, failing if the code is not made up of 8 digits.
+    pub fn verify<T: AsRef<str>>(full_code: T) -> Result<bool> {
+        let d = EAN8::parse(full_code.as_ref())?;
+
+        if d.len() != 8 {
+            return Err(Error::Length);
+        }
+
+        match EAN8::new(d) {
+            Ok(_) => Ok(true),
+            Err(Error::Checksum) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Decodes the given binary digits (as returned by `encode`) back into the 8 digits of
+    /// barcode data, including the check digit.
+    /// Returns Result<String, Error> indicating decode success.
+    pub fn decode(modules: &[u8]) -> Result<String> {
+        if modules.len() != ENCODED_LEN {
+            return Err(Error::Length);
+        }
+
+        if modules[0..3] != LEFT_GUARD || modules[31..36] != MIDDLE_GUARD || modules[64..] != RIGHT_GUARD {
+            return Err(Error::Character);
+        }
+
+        let left = modules[3..31].chunks(7).map(|c| EAN8::decode_digit(0, c));
+        let right = modules[36..64].chunks(7).map(|c| EAN8::decode_digit(2, c));
+        let mut digits = left.chain(right).collect::<Result<Vec<u8>>>()?;
+        let check_digit = digits.pop();
+
+        if check_digit != Some(helpers::modulo_10_checksum(&digits[..], false)) {
+            return Err(Error::Checksum);
+        }
+
+        Ok(EAN8(digits).to_string())
+    }
+
+    // Confirms that the given encoding decodes back to this barcode's data.
+    fn check(&self, modules: &[u8]) -> Result<()> {
+        match EAN8::decode(modules) {
+            Ok(ref d) if *d == self.to_string() => Ok(()),
+            _ => Err(Error::Generate),
+        }
+    }
+
+    fn decode_digit(side: usize, encoding: &[u8]) -> Result<u8> {
+        match ENCODINGS[side].iter().position(|e| e[..] == *encoding) {
+            Some(d) => Ok(d as u8),
+            None => Err(Error::Character),
+        }
     }
 
     /// Calculates the checksum digit using a weighting algorithm.
@@ -94,6 +164,17 @@ impl EAN8 {
     }
 }
 
+impl fmt::Display for EAN8 {
+    /// Formats the barcode data, including the check digit.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for d in self.0.iter().chain(Some(self.checksum_digit()).iter()) {
+            write!(f, "{}", d)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl Parse for EAN8 {
     /// Returns the valid length of data acceptable in this type of barcode.
     fn valid_len() -> Range<u32> {
@@ -106,6 +187,40 @@ impl Parse for EAN8 {
     }
 }
 
+impl Barcode for EAN8 {
+    fn encode(&self) -> Vec<u8> {
+        EAN8::encode(self)
+    }
+
+    /// EAN-8 specifies a nominal bar height of 18.23mm for a 22.11mm wide symbol.
+    fn min_height(&self, width_px: u32) -> u32 {
+        helpers::height_from_ratio(width_px, 83)
+    }
+
+    fn guard_ranges(&self) -> Vec<Range<usize>> {
+        vec![0..3, 31..36, 64..67]
+    }
+
+    /// The guards and check digit are overhead.
+    fn density(&self) -> Density {
+        Density::new(self.encode().len(), self.0.len() * 7, self.metadata().quiet_zone_modules)
+    }
+
+    fn metadata(&self) -> SymbologyMeta {
+        SymbologyMeta::new(&self.guard_ranges(), 7)
+    }
+
+    fn self_check(&self) -> Result<()> {
+        self.check(&self.encode())
+    }
+
+    fn human_readable(&self) -> Vec<(Range<usize>, String)> {
+        let digits = self.to_string();
+
+        vec![(3..31, digits[..4].to_string()), (36..64, digits[4..].to_string())]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use sym::ean8::*;
@@ -138,6 +253,60 @@ mod tests {
         assert_eq!(ean8.err().unwrap(), Error::Length);
     }
 
+    #[test]
+    fn new_ean8_with_check_digit() {
+        let ean8 = EAN8::new("96385074");
+
+        assert!(ean8.is_ok());
+        assert_eq!(ean8.unwrap().encode(), EAN8::new("9638507").unwrap().encode());
+    }
+
+    #[test]
+    fn ean8_verify() {
+        assert_eq!(EAN8::verify("96385074"), Ok(true));
+        assert_eq!(EAN8::verify("96385075"), Ok(false));
+        assert_eq!(EAN8::verify("9638507"), Err(Error::Length));
+        assert_eq!(EAN8::verify("9638507A"), Err(Error::Character));
+    }
+
+    #[test]
+    fn invalid_check_digit_ean8() {
+        let ean8 = EAN8::new("96385075");
+
+        assert_eq!(ean8.err().unwrap(), Error::Checksum);
+    }
+
+    #[test]
+    fn ean8_check_digit() {
+        assert_eq!(EAN8::new("9638507").unwrap().to_string(), "96385074");
+        assert_eq!(EAN8::new("5512345").unwrap().to_string(), "55123457");
+    }
+
+    #[test]
+    fn ean8_decode() {
+        let ean81 = EAN8::new("5512345").unwrap();
+        let ean82 = EAN8::new("96385074").unwrap();
+
+        assert_eq!(EAN8::decode(&ean81.encode()[..]).unwrap(), "55123457");
+        assert_eq!(EAN8::decode(&ean82.encode()[..]).unwrap(), "96385074");
+    }
+
+    #[test]
+    fn invalid_ean8_decode() {
+        let mut modules = EAN8::new("5512345").unwrap().encode();
+
+        assert_eq!(EAN8::decode(&modules[1..]).err().unwrap(), Error::Length);
+
+        // Corrupt the first data digit.
+        modules[4] = 1 - modules[4];
+        assert_eq!(EAN8::decode(&modules[..]).err().unwrap(), Error::Character);
+
+        // Swap in the encoding of another valid digit.
+        let mut modules = EAN8::new("5512345").unwrap().encode();
+        modules[3..10].copy_from_slice(&ENCODINGS[0][6]);
+        assert_eq!(EAN8::decode(&modules[..]).err().unwrap(), Error::Checksum);
+    }
+
     #[test]
     fn ean8_encode() {
         let ean81 = EAN8::new("5512345").unwrap(); // Check digit: 7
@@ -6,7 +6,7 @@
 //!
 //! These supplemental barcodes never appear without a full EAN-13 barcode alongside them.
 
-use sym::{Parse, helpers};
+use sym::{Barcode, Parse, SymbologyMeta, helpers};
 use error::{Error, Result};
 use sym::ean13::ENCODINGS;
 use std::ops::Range;
@@ -29,6 +29,8 @@ const EAN2_PARITY: [[usize; 5]; 4] = [
 ];
 
 /// The Supplemental EAN barcode type.
+///
+/// There is no decoder for this type, so `Barcode::self_check` always passes.
 #[derive(Debug)]
 pub enum EANSUPP {
     /// EAN-2 supplemental barcode type.
@@ -44,9 +46,9 @@ impl EANSUPP {
     /// the length of `data`.
     pub fn new<T: AsRef<str>>(data: T) -> Result<EANSUPP> {
         EANSUPP::parse(data.as_ref()).and_then(|d| {
-            let digits: Vec<u8> = d.chars()
-                                   .map(|c| c.to_digit(10).expect("Unknown character") as u8)
-                                   .collect();
+            let digits = d.chars()
+                          .map(|c| c.to_digit(10).map(|d| d as u8).ok_or(Error::Character))
+                          .collect::<Result<Vec<u8>>>()?;
 
             match digits.len() {
                 2 => Ok(EANSUPP::EAN2(digits)),
@@ -139,6 +141,28 @@ impl Parse for EANSUPP {
     }
 }
 
+impl Barcode for EANSUPP {
+    fn encode(&self) -> Vec<u8> {
+        EANSUPP::encode(self)
+    }
+
+    /// Supplementals share the 21.90mm bar height of the EAN-13 they accompany.
+    fn min_height(&self, width_px: u32) -> u32 {
+        match *self {
+            EANSUPP::EAN2(_) => helpers::height_from_ratio(width_px, 332),
+            EANSUPP::EAN5(_) => helpers::height_from_ratio(width_px, 142),
+        }
+    }
+
+    /// The guard is not extended like those of EAN-13, so is only reported here. The space
+    /// between the EAN-13 and the supplemental is covered by the former's quiet zone.
+    fn metadata(&self) -> SymbologyMeta {
+        let left_guard = 0..LEFT_GUARD.len();
+
+        SymbologyMeta::new(&[left_guard], 5)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use sym::ean_supp::*;
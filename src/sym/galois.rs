@@ -0,0 +1,211 @@
+//! Arithmetic over the Galois field GF(256).
+//!
+//! The Reed-Solomon error correction used by 2D symbologies (QR, Data Matrix, Aztec, etc) is
+//! computed over GF(256). The log/antilog tables for each field, along with the generator
+//! polynomials, are computed on first use and shared between encoders.
+//!
+//! For example:
+//!
+//! ```rust
+//! use barcoders::sym::galois;
+//!
+//! let field = galois::qr();
+//!
+//! assert_eq!(field.mul(0x80, 2), 0x1D);
+//! assert_eq!(field.inverse(2), Some(0x8E));
+//! ```
+
+use std::fmt;
+use std::sync::OnceLock;
+
+// Generator polynomials can be of any degree up to the number of non-zero field elements.
+const MAX_DEGREE: usize = 255;
+
+static QR: OnceLock<Field> = OnceLock::new();
+static DATA_MATRIX: OnceLock<Field> = OnceLock::new();
+
+/// A GF(256) field, defined by its primitive polynomial.
+pub struct Field {
+    exp: [u8; 512],
+    log: [u8; 256],
+    base: usize,
+    generators: Vec<OnceLock<Vec<u8>>>,
+}
+
+/// Returns the field used by QR codes (primitive polynomial 0x11D, generator roots from α^0).
+pub fn qr() -> &'static Field {
+    QR.get_or_init(|| Field::new(0x11D, 0))
+}
+
+/// Returns the field used by Data Matrix (primitive polynomial 0x12D, generator roots from α^1).
+pub fn data_matrix() -> &'static Field {
+    DATA_MATRIX.get_or_init(|| Field::new(0x12D, 1))
+}
+
+impl Field {
+    /// Creates a new field from the given primitive polynomial (eg: 0x11D for x^8+x^4+x^3+x^2+1).
+    /// `base` is the power of α of the first root of each generator polynomial.
+    pub fn new(primitive: u16, base: usize) -> Field {
+        let mut exp = [0; 512];
+        let mut log = [0; 256];
+        let mut x: u16 = 1;
+
+        for (i, e) in exp.iter_mut().enumerate().take(255) {
+            *e = x as u8;
+            log[x as usize] = i as u8;
+
+            x <<= 1;
+            if x & 0x100 != 0 {
+                x ^= primitive;
+            }
+        }
+
+        // Doubling the antilog table avoids a modulo when multiplying.
+        for i in 255..512 {
+            exp[i] = exp[i - 255];
+        }
+
+        Field {
+            exp,
+            log,
+            base,
+            generators: (0..=MAX_DEGREE).map(|_| OnceLock::new()).collect(),
+        }
+    }
+
+    /// Returns α raised to the given power.
+    pub fn exp(&self, power: usize) -> u8 {
+        self.exp[power % 255]
+    }
+
+    /// Returns the discrete logarithm of `a`, or None when `a` is zero.
+    pub fn log(&self, a: u8) -> Option<usize> {
+        match a {
+            0 => None,
+            _ => Some(self.log[a as usize] as usize),
+        }
+    }
+
+    /// Multiplies two field elements.
+    pub fn mul(&self, a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 {
+            0
+        } else {
+            self.exp[self.log[a as usize] as usize + self.log[b as usize] as usize]
+        }
+    }
+
+    /// Returns the multiplicative inverse of `a`, or None when `a` is zero.
+    pub fn inverse(&self, a: u8) -> Option<u8> {
+        self.log(a).map(|l| self.exp[255 - l])
+    }
+
+    /// Divides `a` by `b`. Returns None when `b` is zero.
+    pub fn div(&self, a: u8, b: u8) -> Option<u8> {
+        self.inverse(b).map(|i| self.mul(a, i))
+    }
+
+    /// Returns the Reed-Solomon generator polynomial of the given degree, with coefficients
+    /// ordered from the highest power down. The leading coefficient is always 1.
+    ///
+    /// Returns None if the degree is zero or larger than the field allows.
+    pub fn generator(&self, degree: usize) -> Option<&[u8]> {
+        if degree == 0 || degree > MAX_DEGREE {
+            return None;
+        }
+
+        let poly = self.generators[degree].get_or_init(|| {
+            let mut poly = vec![1];
+
+            // Multiply out (x - α^base)(x - α^(base+1))...
+            for i in 0..degree {
+                let root = self.exp(self.base + i);
+                let mut next = vec![0; poly.len() + 1];
+
+                for (j, &c) in poly.iter().enumerate() {
+                    next[j] ^= c;
+                    next[j + 1] ^= self.mul(c, root);
+                }
+
+                poly = next;
+            }
+
+            poly
+        });
+
+        Some(&poly[..])
+    }
+}
+
+impl fmt::Debug for Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Field {{ base: {} }}", self.base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sym::galois::*;
+
+    #[test]
+    fn galois_exp_log() {
+        let field = qr();
+
+        assert_eq!(field.exp(0), 1);
+        assert_eq!(field.exp(8), 0x1D);
+        assert_eq!(field.exp(255), 1);
+        assert_eq!(field.log(0x1D), Some(8));
+        assert_eq!(field.log(0), None);
+    }
+
+    #[test]
+    fn galois_mul() {
+        let field = qr();
+
+        assert_eq!(field.mul(3, 7), 9);
+        assert_eq!(field.mul(0x80, 2), 0x1D);
+        assert_eq!(field.mul(0, 0x53), 0);
+        assert_eq!(field.mul(0x53, 1), 0x53);
+        assert_eq!(data_matrix().mul(0x80, 2), 0x2D);
+    }
+
+    #[test]
+    fn galois_inverse() {
+        let qr = qr();
+        let dm = data_matrix();
+
+        assert_eq!(qr.inverse(2), Some(0x8E));
+        assert_eq!(dm.inverse(2), Some(0x96));
+        assert_eq!(qr.inverse(0), None);
+        assert_eq!(qr.div(9, 7), Some(3));
+        assert_eq!(qr.div(9, 0), None);
+
+        for a in 1..=255 {
+            assert_eq!(qr.mul(a, qr.inverse(a).unwrap()), 1);
+            assert_eq!(dm.mul(a, dm.inverse(a).unwrap()), 1);
+        }
+    }
+
+    #[test]
+    fn galois_generator() {
+        let field = qr();
+        let logs: Vec<usize> = field.generator(7)
+                                    .unwrap()
+                                    .iter()
+                                    .map(|&c| field.log(c).unwrap())
+                                    .collect();
+
+        // ISO/IEC 18004 Annex A: 7 error correction codewords.
+        assert_eq!(logs, vec![0, 87, 229, 146, 149, 238, 102, 21]);
+        assert_eq!(field.generator(0), None);
+        assert_eq!(field.generator(256), None);
+    }
+
+    #[test]
+    fn galois_generator_data_matrix() {
+        let field = data_matrix();
+
+        // ISO/IEC 16022 Annex E: 5 error correction codewords.
+        assert_eq!(field.generator(5).unwrap(), &[1, 62, 111, 15, 48, 228]);
+    }
+}
@@ -1,3 +1,13 @@
+//! Helper functions shared by the symbologies, which are also useful when working with
+//! encoded barcodes.
+
+use checksum;
+use error::{Error, Result};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+const MM_PER_INCH: f64 = 25.4;
+
 /// Joins and flattens the given slice of &[u8] slices into a Vec<u8>.
 /// TODO: Work out how to use join_iters with slices and then remove this function.
 pub fn join_slices(slices: &[&[u8]]) -> Vec<u8> {
@@ -16,27 +26,190 @@ pub fn join_iters<'a, T: Iterator>(iters: T) -> Vec<u8>
 }
 
 /// Calculates the checksum digit using a modulo-10 weighting algorithm.
+/// The digits at even indices are tripled, unless `even_start` is set, in which case the odd
+/// ones are. See `checksum::mod10`, which always triples the rightmost digit.
 pub fn modulo_10_checksum(data: &[u8], even_start: bool) -> u8 {
-    let mut odds = 0;
-    let mut evens = 0;
+    // EAN-13 (and some others?) barcodes use EVEN-first weighting to maintain
+    // backwards compatibility.
+    if (data.len() % 2 == 1) != even_start {
+        checksum::mod10(data)
+    } else {
+        // A trailing zero takes the rightmost weight without changing the sum.
+        let mut padded = data.to_vec();
+        padded.push(0);
+        checksum::mod10(&padded)
+    }
+}
+
+/// Calculates a height that is `percent` percent of the given width, rounding up.
+pub fn height_from_ratio(width: u32, percent: u32) -> u32 {
+    ((u64::from(width) * u64::from(percent) + 99) / 100).min(u64::from(u32::MAX)) as u32
+}
+
+/// Calculates the largest X dimension (in pixels, and at least 1) at which the given number of
+/// modules fits within `width_mm` millimeters when printed at `dpi` pixels per inch.
+pub fn xdim_from_physical(modules: usize, width_mm: f64, dpi: u32) -> u32 {
+    let width_px = (width_mm / MM_PER_INCH) * f64::from(dpi);
+
+    // The small tolerance stops widths reported by `width_mm` from rounding down a pixel.
+    match modules {
+        0 => 1,
+        m => (((width_px / m as f64) + 1e-9).floor() as u32).max(1),
+    }
+}
 
-    for (i, d) in data.iter().enumerate() {
-        match i % 2 {
-            1 => odds += *d,
-            _ => evens += *d,
+/// Calculates the printed width in millimeters of the given number of modules, at `xdim`
+/// pixels per module and `dpi` pixels per inch.
+pub fn width_mm(modules: usize, xdim: u32, dpi: u32) -> f64 {
+    (modules as f64 * f64::from(xdim) / f64::from(dpi)) * MM_PER_INCH
+}
+
+/// Run-length-encodes the given modules into (is_dark, run_length) pairs. Any non-zero module
+/// is considered dark.
+pub fn modules_to_rle(modules: &[u8]) -> Vec<(bool, u32)> {
+    let mut runs: Vec<(bool, u32)> = vec![];
+
+    for &m in modules {
+        let dark = m != 0;
+
+        match runs.last_mut() {
+            Some(&mut (d, ref mut len)) if d == dark => *len += 1,
+            _ => runs.push((dark, 1)),
         }
     }
 
-    // EAN-13 (and some others?) barcodes use EVEN-first weighting to maintain
-    // backwards compatibility.
-    if even_start {
-        odds *= 3;
-    } else {
-        evens *= 3;
+    runs
+}
+
+/// Expands the given (is_dark, run_length) pairs back into a Vec<u8> of binary digits.
+pub fn rle_to_modules(runs: &[(bool, u32)]) -> Vec<u8> {
+    runs.iter()
+        .flat_map(|&(dark, len)| (0..len).map(move |_| dark as u8))
+        .collect()
+}
+
+/// Swaps the dark and light modules of the given binary digits, for drawing inverted (light on
+/// dark) barcodes.
+pub fn invert_modules(modules: &[u8]) -> Vec<u8> {
+    modules.iter()
+           .map(|&m| if m == 0 { 1 } else { 0 })
+           .collect()
+}
+
+/// Formats the given binary digits as a string of '0's and '1's, which makes for readable
+/// assertions against encoded barcodes.
+pub fn modules_to_string(modules: &[u8]) -> String {
+    modules.iter()
+           .map(|&m| if m == 0 { '0' } else { '1' })
+           .collect()
+}
+
+/// Parses a string of '0's and '1's (such as a pattern from a specification table) into binary
+/// digits. Returns Result<Vec<u8>, Error>, failing on any other character.
+pub fn string_to_modules(pattern: &str) -> Result<Vec<u8>> {
+    pattern.chars()
+           .map(|c| match c {
+               '0' => Ok(0),
+               '1' => Ok(1),
+               _ => Err(Error::Character),
+           })
+           .collect()
+}
+
+// Resolves per-character pattern overrides into keys of a symbology's table, as found by
+// `keys` (which returns none for an unknown character). Each pattern must be `width` binary
+// digits, as the symbologies that accept overrides encode every character in the same width.
+pub(crate) fn resolve_overrides<K, F>(overrides: &HashMap<char, Vec<u8>>, width: usize, keys: F) -> Result<HashMap<K, Vec<u8>>>
+        where K: Hash + Eq, F: Fn(char) -> Vec<K> {
+    let mut resolved = HashMap::new();
+
+    for (&c, pattern) in overrides {
+        let found = keys(c);
+
+        if found.is_empty() || pattern.iter().any(|&m| m > 1) {
+            return Err(Error::Character);
+        }
+
+        if pattern.len() != width {
+            return Err(Error::Length);
+        }
+
+        resolved.extend(found.into_iter().map(|k| (k, pattern.clone())));
+    }
+
+    Ok(resolved)
+}
+
+// Splits the encoding of a discrete barcode into the patterns of its characters, each made up
+// of `elements` bars and spaces and followed by a gap of `gap` modules (except for the last).
+// Fails with `Error::Generate` if the encoding doesn't divide up that way.
+pub(crate) fn split_discrete(modules: &[u8], elements: usize, gap: usize) -> Result<Vec<Vec<u8>>> {
+    let runs = modules_to_rle(modules);
+
+    if runs.len() % (elements + 1) != elements || runs.first().map_or(true, |r| !r.0) {
+        return Err(Error::Generate);
     }
 
-    match 10 - ((odds + evens) % 10) {
-        10 => 0,
-        n => n,
+    runs.chunks(elements + 1)
+        .map(|c| match c.get(elements) {
+            Some(&(_, len)) if len as usize != gap => Err(Error::Generate),
+            _ => Ok(rle_to_modules(&c[..elements])),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use sym::helpers::*;
+    use error::Error;
+    use sym::ean8::EAN8;
+    use sym::Barcode;
+
+    #[test]
+    fn physical_xdim() {
+        // A nominal (100%) EAN-13 is 37.29mm wide, which at 300dpi is 440 pixels.
+        assert_eq!(xdim_from_physical(95, 37.29, 300), 4);
+        assert_eq!(xdim_from_physical(95, 1.0, 300), 1);
+        assert!((width_mm(95, 4, 300) - 32.173).abs() < 0.001);
+        assert_eq!(xdim_from_physical(95, width_mm(95, 4, 300), 300), 4);
+    }
+
+    #[test]
+    fn split_discrete_characters() {
+        let modules = [1, 0, 1, 1, 0, 0, 1, 1, 0, 1];
+
+        assert_eq!(split_discrete(&modules, 3, 2), Ok(vec![vec![1, 0, 1, 1], vec![1, 1, 0, 1]]));
+        assert_eq!(split_discrete(&modules, 3, 1), Err(Error::Generate));
+        assert_eq!(split_discrete(&modules[..9], 3, 2), Err(Error::Generate));
+        assert_eq!(split_discrete(&modules[1..], 3, 2), Err(Error::Generate));
+        assert_eq!(split_discrete(&[], 3, 2), Err(Error::Generate));
+    }
+
+    #[test]
+    fn modules_to_rle_merges_runs() {
+        assert_eq!(modules_to_rle(&[1, 0, 0, 1, 1, 1, 0]), vec![(true, 1), (false, 2), (true, 3), (false, 1)]);
+        assert_eq!(modules_to_rle(&[]), vec![]);
+    }
+
+    #[test]
+    fn rle_round_trip() {
+        let ean8 = EAN8::new("1234567").unwrap();
+        let rle = ean8.encode_rle();
+
+        assert_eq!(rle_to_modules(&rle), ean8.encode());
+        assert!(rle.windows(2).all(|w| w[0].0 != w[1].0));
+        assert_eq!(rle[..3], [(true, 1), (false, 1), (true, 1)]);
+    }
+
+    #[test]
+    fn modules_string_round_trip() {
+        let pattern = "10100110010010011011110101000110";
+
+        assert_eq!(modules_to_string(&string_to_modules(pattern).unwrap()), pattern);
+        assert_eq!(string_to_modules("101").unwrap(), vec![1, 0, 1]);
+        assert_eq!(modules_to_string(&EAN8::new("1234567").unwrap().encode()[..4]), "1010");
+        assert_eq!(string_to_modules(""), Ok(vec![]));
+        assert_eq!(string_to_modules("10 1").err().unwrap(), Error::Character);
+        assert_eq!(string_to_modules("102").err().unwrap(), Error::Character);
     }
 }
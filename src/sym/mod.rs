@@ -11,7 +11,12 @@
 //! let encoded = barcode.encode();
 //! ```
 //! Each encoder accepts a `String` to be encoded. Valid data is barcode-specific and thus
-//! constructors return an Option<T>.
+//! constructors return a Result<T, Error>.
+//!
+//! All validation happens in the constructors, which only ever store data that can be encoded.
+//! As such, encoding never fails (or panics) once a barcode has been created.
+//!
+//! All symbologies also implement the `Barcode` trait, which allows them to be used generically.
 
 pub mod ean13;
 pub mod ean8;
@@ -20,19 +25,255 @@ pub mod code39;
 pub mod code93;
 pub mod code11;
 pub mod code128;
+pub mod codablock;
 pub mod codabar;
 pub mod tf;
-mod helpers;
+pub mod msi;
+pub mod pharmacode;
+pub mod postal;
+pub mod galois;
+pub mod reed_solomon;
+pub mod helpers;
 
 use std::ops::Range;
 use std::iter::Iterator;
-use error::Error;
+use std::str::FromStr;
+use error::{Error, Result, Warning};
+
+/// Common behaviour shared by each of the supported symbologies.
+pub trait Barcode {
+    /// Encodes the barcode.
+    /// Returns a Vec<u8> of binary digits.
+    fn encode(&self) -> Vec<u8>;
+
+    /// Returns the recommended minimum height (in pixels) of the barcode when rendered at the
+    /// given width (in pixels).
+    ///
+    /// By default this is 15% of the width, which is the general minimum for linear symbologies.
+    fn min_height(&self, width_px: u32) -> u32 {
+        helpers::height_from_ratio(width_px, 15)
+    }
+
+    /// Returns the recommended maximum number of modules for the barcode, beyond which most
+    /// scanners will struggle. Fixed-length symbologies have no such limit.
+    fn max_modules(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns the ranges of module indices that make up guard patterns, which are
+    /// conventionally drawn extending below the other bars. Most symbologies have none.
+    fn guard_ranges(&self) -> Vec<Range<usize>> {
+        vec![]
+    }
+
+    /// Returns the default human-readable text, as pieces of text each centered on a range of
+    /// module indices. By default there is none.
+    fn human_readable(&self) -> Vec<(Range<usize>, String)> {
+        vec![]
+    }
+
+    /// Returns structural metadata about the barcode, for laying it out without rendering.
+    ///
+    /// By default this is built from `guard_ranges`, with the 10 module quiet zone that most
+    /// linear symbologies require.
+    fn metadata(&self) -> SymbologyMeta {
+        SymbologyMeta::new(&self.guard_ranges(), 10)
+    }
+
+    /// Encodes the barcode with the given number of blank modules before and after it, for
+    /// layouts that need a wider quiet zone on one side (such as the leading edge of a thermal
+    /// roll). The symmetric value from `metadata` suits most uses.
+    fn encode_with_quiet_zones(&self, left_quiet_zone: usize, right_quiet_zone: usize) -> Vec<u8> {
+        [vec![0; left_quiet_zone], self.encode(), vec![0; right_quiet_zone]].concat()
+    }
+
+    /// Returns the printed width of the barcode (including any guards and check characters) for
+    /// the given X dimension, in the same units. The quiet zone recommended by `metadata` is
+    /// included on both sides if `quiet_zone` is set.
+    fn printed_width(&self, x_dimension: f32, quiet_zone: bool) -> f32 {
+        let quiet_modules = if quiet_zone { 2 * self.metadata().quiet_zone_modules } else { 0 };
+
+        (self.encode().len() + quiet_modules) as f32 * x_dimension
+    }
+
+    /// Returns the number of modules that carry data, along with those that don't (the START
+    /// and STOP patterns, guards, check characters, gaps and the recommended quiet zone).
+    ///
+    /// By default everything but the guards and quiet zone is counted as data.
+    fn density(&self) -> Density {
+        let meta = self.metadata();
+        let modules = self.encode().len();
+
+        Density::new(modules, modules - meta.guard_positions.len(), meta.quiet_zone_modules)
+    }
+
+    /// Decodes the encoded barcode and confirms that it matches the data the barcode was created
+    /// from, as a safeguard against encoding regressions.
+    /// Returns `Error::Generate` on a mismatch. Symbologies without a decoder (2-of-5, MSI,
+    /// Pharmacode and the EAN supplements) always pass.
+    fn self_check(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Encodes the barcode as a run-length-encoded sequence of (is_dark, run_length) pairs,
+    /// which is far more compact than one byte per module. Adjacent modules of the same color
+    /// are always merged into a single run. `helpers::rle_to_modules` reverses this.
+    fn encode_rle(&self) -> Vec<(bool, u32)> {
+        helpers::modules_to_rle(&self.encode())
+    }
+
+    /// Checks the encoded barcode against the practical capacity of the symbology.
+    /// Returns a Vec of warnings, which will be empty if no problems were found.
+    fn validate_capacity(&self) -> Vec<Warning> {
+        let modules = self.encode().len();
+
+        match self.max_modules() {
+            Some(recommended) if modules > recommended => {
+                vec![Warning::Capacity{modules, recommended}]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+/// Structural metadata shared by a barcode and the generators. See `Barcode::metadata`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymbologyMeta {
+    /// The indices of the modules making up guard (and center) patterns.
+    pub guard_positions: Vec<usize>,
+    /// The recommended number of blank modules on each side of the barcode.
+    pub quiet_zone_modules: usize,
+    /// Whether information is carried by the height of the bars (as with postal symbologies),
+    /// rather than solely their width.
+    pub uses_height_modulation: bool,
+}
+
+impl SymbologyMeta {
+    /// Creates metadata for a barcode with the given guard patterns and quiet zone, whose bars
+    /// are all the same height.
+    pub fn new(guards: &[Range<usize>], quiet_zone_modules: usize) -> SymbologyMeta {
+        SymbologyMeta {
+            guard_positions: guards.iter().cloned().flatten().collect(),
+            quiet_zone_modules,
+            uses_height_modulation: false,
+        }
+    }
+}
+
+/// The number of modules devoted to data in a barcode, versus overhead. See `Barcode::density`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Density {
+    /// The number of modules encoding the data itself.
+    pub data_modules: usize,
+    /// The number of modules that carry no data, including the quiet zone on both sides.
+    pub overhead_modules: usize,
+}
+
+impl Density {
+    /// Creates a density for a barcode of `modules` modules (excluding the quiet zone), of
+    /// which `data_modules` carry data, with `quiet_zone_modules` blank modules on each side.
+    pub fn new(modules: usize, data_modules: usize, quiet_zone_modules: usize) -> Density {
+        Density {
+            data_modules,
+            overhead_modules: modules - data_modules + (2 * quiet_zone_modules),
+        }
+    }
+}
+
+/// The supported symbologies. Useful for selecting a symbology at runtime.
+///
+/// ```rust
+/// use barcoders::sym::Symbology;
+///
+/// let symbology: Symbology = "ean13".parse().unwrap();
+/// let barcode = symbology.barcode("750103131130").unwrap();
+/// let encoded = barcode.encode();
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Symbology {
+    /// EAN-13 (also UPC-A, JAN and Bookland).
+    EAN13,
+    /// EAN-8.
+    EAN8,
+    /// EAN-2 or EAN-5 supplemental, depending on the length of the data.
+    EANSUPP,
+    /// Code11 (USD-8).
+    Code11,
+    /// Code39.
+    Code39,
+    /// Code93.
+    Code93,
+    /// Code128.
+    Code128,
+    /// Codabar.
+    Codabar,
+    /// Interleaved 2-of-5.
+    ITF,
+    /// Standard 2-of-5.
+    STF,
+    /// MSI (Modified Plessey).
+    MSI,
+    /// Pharmacode.
+    Pharmacode,
+}
+
+impl Symbology {
+    /// Creates a new barcode of this symbology.
+    /// Returns Result<Box<Barcode>, Error> indicating parse success.
+    pub fn barcode<T: AsRef<str>>(self, data: T) -> Result<Box<dyn Barcode>> {
+        let data = data.as_ref();
+
+        Ok(match self {
+            Symbology::EAN13 => Box::new(ean13::EAN13::new(data)?),
+            Symbology::EAN8 => Box::new(ean8::EAN8::new(data)?),
+            Symbology::EANSUPP => Box::new(ean_supp::EANSUPP::new(data)?),
+            Symbology::Code11 => Box::new(code11::Code11::new(data)?),
+            Symbology::Code39 => Box::new(code39::Code39::new(data)?),
+            Symbology::Code93 => Box::new(code93::Code93::new(data)?),
+            Symbology::Code128 => Box::new(code128::Code128::new(data)?),
+            Symbology::Codabar => Box::new(codabar::Codabar::new(data)?),
+            Symbology::ITF => Box::new(tf::TF::interleaved(data)?),
+            Symbology::STF => Box::new(tf::TF::standard(data)?),
+            Symbology::MSI => Box::new(msi::MSI::new(data)?),
+            Symbology::Pharmacode => Box::new(pharmacode::Pharmacode::new(data)?),
+        })
+    }
+}
+
+impl FromStr for Symbology {
+    type Err = Error;
+
+    /// Parses a symbology from its (case-insensitive) name, ignoring dashes and underscores.
+    /// For example: "ean13", "EAN-13", "upca", "code128" or "itf".
+    fn from_str(s: &str) -> Result<Symbology> {
+        let name: String = s.chars()
+                            .filter(|&c| c != '-' && c != '_')
+                            .flat_map(|c| c.to_lowercase())
+                            .collect();
+
+        match &name[..] {
+            "ean13" | "upca" | "jan" | "bookland" => Ok(Symbology::EAN13),
+            "ean8" => Ok(Symbology::EAN8),
+            "ean2" | "ean5" | "eansupp" => Ok(Symbology::EANSUPP),
+            "code11" | "usd8" => Ok(Symbology::Code11),
+            "code39" => Ok(Symbology::Code39),
+            "code93" => Ok(Symbology::Code93),
+            "code128" => Ok(Symbology::Code128),
+            "codabar" => Ok(Symbology::Codabar),
+            "itf" | "interleaved2of5" => Ok(Symbology::ITF),
+            "stf" | "standard2of5" => Ok(Symbology::STF),
+            "msi" | "modifiedplessey" => Ok(Symbology::MSI),
+            "pharmacode" | "laetus" => Ok(Symbology::Pharmacode),
+            _ => Err(Error::Symbology),
+        }
+    }
+}
 
 trait Parse {
     fn valid_chars() -> Vec<char>;
     fn valid_len() -> Range<u32>;
 
-    fn parse(data: &str) -> Result<&str, Error> {
+    fn parse(data: &str) -> Result<&str> {
         let valid_chars = Self::valid_chars();
         let valid_len = Self::valid_len();
         let data_len = data.len() as u32;
@@ -54,3 +295,117 @@ trait Parse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use sym::*;
+
+    const SYMBOLOGIES: [Symbology; 12] = [
+        Symbology::EAN13, Symbology::EAN8, Symbology::EANSUPP, Symbology::Code11,
+        Symbology::Code39, Symbology::Code93, Symbology::Code128, Symbology::Codabar,
+        Symbology::ITF, Symbology::STF, Symbology::MSI, Symbology::Pharmacode,
+    ];
+
+    #[test]
+    fn crafted_input_never_panics() {
+        let long = "9".repeat(255);
+        let too_long = "9".repeat(1024);
+        let inputs = ["", "\u{0}", "☺☺☺", "ÀÀÀ", "Ć", "Ć1Ź2", "ĆŹ", "Ž", "A", "AA", "]",
+                      "\u{7f}\u{80}\u{ff}", &long, &too_long];
+
+        for &sym in SYMBOLOGIES.iter() {
+            for data in inputs.iter() {
+                if let Ok(barcode) = sym.barcode(data) {
+                    assert!(barcode.encode().iter().all(|&m| m <= 1));
+                    barcode.validate_capacity();
+                    barcode.min_height(u32::MAX);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn crafted_input_is_rejected() {
+        let too_long = "9".repeat(1024);
+
+        for &sym in SYMBOLOGIES.iter() {
+            assert!(sym.barcode("").is_err());
+            assert!(sym.barcode("☺☺☺").is_err());
+            assert!(sym.barcode(&too_long).is_err());
+        }
+
+        assert_eq!(Symbology::Code128.barcode("Ć1Ź2").err(), Some(Error::Character));
+        assert_eq!(Symbology::Codabar.barcode("1234").err(), Some(Error::Character));
+    }
+
+    #[test]
+    fn constructed_barcodes_always_encode() {
+        let inputs = ["0", "12", "1234567", "96385074", "750103131130", "7501031311309", "12345",
+                      "A1234B", "ÀHELLO", "ĆŹ1234Ɓ1", "Ɓ\u{7f}", "ÀŽa", "TEST-93", "1-2-3",
+                      "99999999999999999999"];
+
+        for &sym in SYMBOLOGIES.iter() {
+            for data in inputs.iter() {
+                if let Ok(barcode) = sym.barcode(data) {
+                    assert!(!barcode.encode().is_empty());
+                    assert!(barcode.encode().iter().all(|&m| m <= 1));
+                    assert_eq!(barcode.self_check(), Ok(()), "{:?} {:?}", sym, data);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn constructors_report_every_error() {
+        assert_eq!(Symbology::EAN13.barcode("7501031311308").err(), Some(Error::Checksum));
+        assert_eq!(Symbology::EAN8.barcode("96385075").err(), Some(Error::Checksum));
+        assert_eq!(Symbology::Code128.barcode("Ć123").err(), Some(Error::Character));
+        assert_eq!(Symbology::Code128.barcode("Ć12A").err(), Some(Error::Character));
+        assert_eq!(Symbology::Code128.barcode("Ɓ\u{0006}").err(), Some(Error::Character));
+        assert_eq!(Symbology::Code128.barcode("ÀŽ").err(), Some(Error::Character));
+        assert_eq!(Symbology::Code39.barcode("abc").err(), Some(Error::Character));
+        assert_eq!(Symbology::ITF.barcode("12a4").err(), Some(Error::Character));
+        assert_eq!(Symbology::Pharmacode.barcode("-42").err(), Some(Error::Character));
+        assert_eq!(Symbology::Pharmacode.barcode("131071").err(), Some(Error::Length));
+    }
+
+    #[test]
+    fn asymmetric_quiet_zones() {
+        let barcode = Symbology::Code39.barcode("TEST").unwrap();
+        let encoded = barcode.encode_with_quiet_zones(12, 6);
+        let leading = encoded.iter().take_while(|&&m| m == 0).count();
+        let trailing = encoded.iter().rev().take_while(|&&m| m == 0).count();
+
+        // Code39 begins and ends with a bar, so every blank module belongs to a quiet zone.
+        assert_eq!((leading, trailing), (12, 6));
+        assert_eq!(encoded[12..encoded.len() - 6], barcode.encode()[..]);
+    }
+
+    #[test]
+    fn density() {
+        let code39 = Symbology::Code39.barcode("BARCODERS1234").unwrap().density();
+        let code128 = Symbology::Code128.barcode("ƁBARCODERS1234").unwrap().density();
+        let ean13 = Symbology::EAN13.barcode("750103131130").unwrap().density();
+
+        // Code39's gaps between characters outweigh Code128's extra checksum symbol.
+        assert_eq!(code39, Density{data_modules: 13 * 12, overhead_modules: 24 + 14 + 20});
+        assert_eq!(code128, Density{data_modules: 13 * 11, overhead_modules: 11 + 11 + 13 + 20});
+        assert!(code128.overhead_modules < code39.overhead_modules);
+        assert_eq!(ean13, Density{data_modules: 11 * 7, overhead_modules: 11 + 7 + 18});
+    }
+
+    #[test]
+    fn self_check_passes() {
+        let inputs = [(Symbology::EAN13, "750103131130"), (Symbology::EAN13, "012345678901"),
+                      (Symbology::EAN8, "9638507"), (Symbology::Code39, "TEST8052"),
+                      (Symbology::Code128, "ÀHELLOĆ1234Ɓabc"), (Symbology::Code93, "TEST93"),
+                      (Symbology::Code11, "123-45"), (Symbology::Codabar, "A40156B"),
+                      (Symbology::ITF, "1234567")];
+
+        for &(sym, data) in inputs.iter() {
+            assert_eq!(sym.barcode(data).unwrap().self_check(), Ok(()));
+        }
+
+        assert_eq!(code39::Code39::with_checksum("TEST8052").unwrap().self_check(), Ok(()));
+    }
+}
@@ -0,0 +1,141 @@
+//! Encoder for MSI barcodes.
+//!
+//! MSI (also Modified Plessey) is used mainly for inventory control, such as marking shelves
+//! in warehouses and supermarkets. Each digit is encoded as four bits, and a modulo-10 (Luhn)
+//! check digit is appended.
+//!
+//! Leading and trailing whitespace is trimmed from the data. Leading zeros are significant and
+//! always encoded, while signs (such as `-`) are rejected with `Error::Character`.
+
+use sym::{Barcode, Density, Parse};
+use sym::helpers;
+use error::{Error, Result};
+use checksum;
+use std::ops::Range;
+use std::char;
+
+const START: [u8; 3] = [1, 1, 0];
+const STOP: [u8; 4] = [1, 0, 0, 1];
+const ZERO: [u8; 3] = [1, 0, 0];
+const ONE: [u8; 3] = [1, 1, 0];
+
+/// The MSI barcode type.
+///
+/// There is no decoder for this type, so `Barcode::self_check` always passes.
+#[derive(Debug)]
+pub struct MSI(Vec<u8>);
+
+impl MSI {
+    /// Creates a new barcode, computing a check digit and appending it to the data for
+    /// encoding.
+    /// Returns Result<MSI, Error> indicating parse success.
+    pub fn new<T: AsRef<str>>(data: T) -> Result<MSI> {
+        let mut digits = MSI::parse(data.as_ref().trim())?
+                             .chars()
+                             .map(|c| c.to_digit(10).map(|d| d as u8).ok_or(Error::Character))
+                             .collect::<Result<Vec<u8>>>()?;
+        let check_digit = checksum::luhn(&digits[..]);
+
+        digits.push(check_digit);
+
+        Ok(MSI(digits))
+    }
+
+    fn raw_data(&self) -> &[u8] {
+        &self.0[..]
+    }
+
+    fn char_encoding(&self, d: u8) -> Vec<u8> {
+        let bits: Vec<&[u8]> = (0..4).rev()
+                                     .map(|i| if d & (1 << i) == 0 { &ZERO[..] } else { &ONE[..] })
+                                     .collect();
+
+        helpers::join_slices(&bits[..])
+    }
+
+    fn payload(&self) -> Vec<u8> {
+        let encodings: Vec<Vec<u8>> = self.raw_data()
+                                          .iter()
+                                          .map(|&d| self.char_encoding(d))
+                                          .collect();
+
+        helpers::join_iters(encodings.iter())
+    }
+
+    /// Encodes the barcode.
+    /// Returns a Vec<u8> of binary digits.
+    pub fn encode(&self) -> Vec<u8> {
+        helpers::join_slices(&[&START[..], &self.payload()[..], &STOP[..]][..])
+    }
+}
+
+impl Parse for MSI {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    /// MSI barcodes are variable-length.
+    fn valid_len() -> Range<u32> {
+        1..256
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+impl Barcode for MSI {
+    fn encode(&self) -> Vec<u8> {
+        MSI::encode(self)
+    }
+
+    /// The START and STOP patterns are overhead. The check digit is counted as data, as with
+    /// the 2-of-5 symbologies.
+    fn density(&self) -> Density {
+        let modules = self.encode().len();
+
+        Density::new(modules, modules - START.len() - STOP.len(), self.metadata().quiet_zone_modules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sym::msi::*;
+    use sym::helpers;
+    use error::Error;
+
+    #[test]
+    fn new_msi() {
+        let msi = MSI::new("1234567");
+
+        assert!(msi.is_ok());
+        assert_eq!(msi.unwrap().raw_data(), &[1, 2, 3, 4, 5, 6, 7, 4]);
+    }
+
+    #[test]
+    fn msi_normalized_data() {
+        let msi = MSI::new("\t0042 ").unwrap();
+
+        // Leading zeros are encoded, unlike with Pharmacode.
+        assert_eq!(msi.raw_data(), &[0, 0, 4, 2, 2]);
+        assert_eq!(MSI::new("-42").err().unwrap(), Error::Character);
+    }
+
+    #[test]
+    fn invalid_data_msi() {
+        let msi = MSI::new("12A4");
+
+        assert_eq!(msi.err().unwrap(), Error::Character);
+    }
+
+    #[test]
+    fn invalid_len_msi() {
+        assert_eq!(MSI::new("").err().unwrap(), Error::Length);
+        assert_eq!(MSI::new("1".repeat(257)).err().unwrap(), Error::Length);
+    }
+
+    #[test]
+    fn msi_encode() {
+        let msi = MSI::new("12").unwrap(); // Check digit: 5
+
+        assert_eq!(helpers::modules_to_string(&msi.encode()), "1101001001001101001001101001001101001101001");
+    }
+}
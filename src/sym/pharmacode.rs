@@ -0,0 +1,151 @@
+//! Encoder for Pharmacode barcodes.
+//!
+//! Pharmacode (also Laetus Pharmacode) is used on pharmaceutical packaging as a check against
+//! mislabelling during packing. Rather than encoding digits, it encodes a single integer from
+//! 3 to 131070, with each narrow or wide bar worth a power of two.
+//!
+//! Leading and trailing whitespace is trimmed from the data. The data is read as an integer, so
+//! leading zeros carry no meaning ("0042" encodes 42), while signs (such as `-`) are rejected
+//! with `Error::Character`.
+
+use sym::{Barcode, Parse};
+use sym::helpers;
+use error::{Error, Result};
+use std::ops::{Range, RangeInclusive};
+use std::char;
+
+const VALUES: RangeInclusive<u32> = 3..=131_070;
+const NARROW: [u8; 1] = [1];
+const WIDE: [u8; 3] = [1, 1, 1];
+const SPACE: [u8; 2] = [0, 0];
+
+/// The Pharmacode barcode type.
+///
+/// There is no decoder for this type, so `Barcode::self_check` always passes.
+#[derive(Clone, Copy, Debug)]
+pub struct Pharmacode(u32);
+
+impl Pharmacode {
+    /// Creates a new barcode from the given integer.
+    /// Returns Result<Pharmacode, Error> indicating parse success, failing with `Error::Length`
+    /// if the integer is outside of 3 to 131070.
+    pub fn new<T: AsRef<str>>(data: T) -> Result<Pharmacode> {
+        // Only digits get past parsing, so this can only fail on overflow.
+        let value = Pharmacode::parse(data.as_ref().trim())?
+                               .parse::<u32>()
+                               .map_err(|_| Error::Length)?;
+
+        if VALUES.contains(&value) {
+            Ok(Pharmacode(value))
+        } else {
+            Err(Error::Length)
+        }
+    }
+
+    /// Returns the integer encoded by the barcode.
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+
+    // Returns whether each bar is wide, from left to right. Counting from the right, the bar at
+    // position n is worth 2^n when narrow and 2^(n + 1) when wide.
+    fn bars(&self) -> Vec<bool> {
+        let mut value = self.0;
+        let mut bars = vec![];
+
+        while value > 0 {
+            let wide = (value & 1) == 0;
+
+            value = (value - if wide { 2 } else { 1 }) / 2;
+            bars.push(wide);
+        }
+
+        bars.reverse();
+        bars
+    }
+
+    /// Encodes the barcode.
+    /// Returns a Vec<u8> of binary digits.
+    pub fn encode(&self) -> Vec<u8> {
+        let bars: Vec<&[u8]> = self.bars()
+                                   .iter()
+                                   .map(|&wide| if wide { &WIDE[..] } else { &NARROW[..] })
+                                   .collect();
+        let spaced: Vec<&[u8]> = bars.iter()
+                                     .enumerate()
+                                     .flat_map(|(i, &b)| if i == 0 { vec![b] } else { vec![&SPACE[..], b] })
+                                     .collect();
+
+        helpers::join_slices(&spaced[..])
+    }
+}
+
+impl Parse for Pharmacode {
+    /// Returns the valid length of data acceptable in this type of barcode. Leading zeros are
+    /// allowed, so the length is not limited to the digits of the largest value.
+    fn valid_len() -> Range<u32> {
+        1..256
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        (0..10).map(|i| char::from_digit(i, 10).unwrap()).collect()
+    }
+}
+
+impl Barcode for Pharmacode {
+    fn encode(&self) -> Vec<u8> {
+        Pharmacode::encode(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sym::pharmacode::*;
+    use sym::helpers;
+    use error::Error;
+
+    #[test]
+    fn new_pharmacode() {
+        let pharmacode = Pharmacode::new("1234");
+
+        assert!(pharmacode.is_ok());
+        assert_eq!(pharmacode.unwrap().value(), 1234);
+    }
+
+    #[test]
+    fn pharmacode_normalized_data() {
+        let pharmacode = Pharmacode::new(" 0042\n").unwrap();
+
+        assert_eq!(pharmacode.value(), 42);
+        assert_eq!(pharmacode.encode(), Pharmacode::new("42").unwrap().encode());
+        assert_eq!(Pharmacode::new("-42").err().unwrap(), Error::Character);
+    }
+
+    #[test]
+    fn invalid_data_pharmacode() {
+        assert_eq!(Pharmacode::new("12A4").err().unwrap(), Error::Character);
+        assert_eq!(Pharmacode::new("+42").err().unwrap(), Error::Character);
+    }
+
+    #[test]
+    fn invalid_len_pharmacode() {
+        assert_eq!(Pharmacode::new("").err().unwrap(), Error::Length);
+        assert_eq!(Pharmacode::new("2").err().unwrap(), Error::Length);
+        assert_eq!(Pharmacode::new("131071").err().unwrap(), Error::Length);
+        assert_eq!(Pharmacode::new("99999999999").err().unwrap(), Error::Length);
+    }
+
+    #[test]
+    fn pharmacode_encode() {
+        let smallest = Pharmacode::new("3").unwrap();
+        let four = Pharmacode::new("4").unwrap();
+        let largest = Pharmacode::new("131070").unwrap();
+
+        assert_eq!(helpers::modules_to_string(&smallest.encode()), "1001");
+        assert_eq!(helpers::modules_to_string(&four.encode()), "100111");
+        assert_eq!(helpers::modules_to_string(&Pharmacode::new("91").unwrap().encode()), "1001110011100111001001");
+        // Sixteen wide bars.
+        assert_eq!(largest.encode().len(), (16 * 3) + (15 * 2));
+    }
+}
@@ -0,0 +1,217 @@
+//! Encoders for 4-state postal barcodes: Dutch KIX and UK RM4SCC.
+//!
+//! Rather than bars and spaces, 4-state barcodes are made up of evenly spaced bars of four
+//! kinds: a short tracker, which may extend up (an ascender), down (a descender) or both (a
+//! full bar). As such, these encoders return a `BarState` for each bar rather than binary digits.
+//!
+//! Both symbologies encode the digits and the uppercase letters, with four bars per character.
+//! RM4SCC adds start and stop bars and a check character, while KIX has neither.
+//!
+//! For example:
+//!
+//! ```rust
+//! use barcoders::sym::postal::*;
+//!
+//! let rm4scc = RM4SCC::new("SN34RD1A").unwrap();
+//! let bars = rm4scc.encode();
+//!
+//! assert_eq!(bars[0], BarState::Ascender);
+//! ```
+
+use sym::Parse;
+use error::Result;
+use std::ops::Range;
+
+// The characters form a 6x6 grid, with the row selecting the ascenders and the column the
+// descenders.
+const CHARS: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// Which of the four bars of a character extend, for each row or column of the grid.
+const HALVES: [[bool; 4]; 6] = [
+    [false, false, true, true], [false, true, false, true], [false, true, true, false],
+    [true, false, false, true], [true, false, true, false], [true, true, false, false],
+];
+
+/// A single bar of a 4-state barcode.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BarState {
+    /// A bar extending both above and below the tracker.
+    Full,
+    /// A bar extending above the tracker.
+    Ascender,
+    /// A bar extending below the tracker.
+    Descender,
+    /// The short central bar alone.
+    Tracker,
+}
+
+impl BarState {
+    /// Returns the conventional letter for the bar: F, A, D or T.
+    pub fn to_char(self) -> char {
+        match self {
+            BarState::Full => 'F',
+            BarState::Ascender => 'A',
+            BarState::Descender => 'D',
+            BarState::Tracker => 'T',
+        }
+    }
+
+    /// Returns the vertical extent of the bar as fractions of the full bar height, from the
+    /// top. The tracker occupies the middle third.
+    pub fn extent(self) -> Range<f64> {
+        match self {
+            BarState::Full => 0.0..1.0,
+            BarState::Ascender => 0.0..(2.0 / 3.0),
+            BarState::Descender => (1.0 / 3.0)..1.0,
+            BarState::Tracker => (1.0 / 3.0)..(2.0 / 3.0),
+        }
+    }
+}
+
+/// The KIX (Klantenindex) barcode type, as used by PostNL.
+#[derive(Debug)]
+pub struct KIX(Vec<usize>);
+
+/// The RM4SCC (Royal Mail 4-State Customer Code) barcode type.
+#[derive(Debug)]
+pub struct RM4SCC(Vec<usize>);
+
+// Converts validated data into positions in the CHARS grid.
+fn positions(data: &str) -> Vec<usize> {
+    data.chars()
+        .filter_map(|c| CHARS.find(c))
+        .collect()
+}
+
+// Appends the four bars of the character at the given position in the CHARS grid.
+fn push_char(into: &mut Vec<BarState>, position: usize) {
+    let ascenders = HALVES[position / 6];
+    let descenders = HALVES[position % 6];
+
+    into.extend(ascenders.iter().zip(descenders.iter()).map(|(&a, &d)| match (a, d) {
+        (true, true) => BarState::Full,
+        (true, false) => BarState::Ascender,
+        (false, true) => BarState::Descender,
+        (false, false) => BarState::Tracker,
+    }));
+}
+
+impl KIX {
+    /// Creates a new barcode.
+    /// Returns Result<KIX, Error> indicating parse success.
+    pub fn new<T: AsRef<str>>(data: T) -> Result<KIX> {
+        KIX::parse(data.as_ref()).map(|d| KIX(positions(d)))
+    }
+
+    /// Encodes the barcode.
+    /// Returns a Vec<BarState> of the bars from left to right.
+    pub fn encode(&self) -> Vec<BarState> {
+        let mut enc = vec![];
+
+        for &p in &self.0 {
+            push_char(&mut enc, p);
+        }
+
+        enc
+    }
+}
+
+impl RM4SCC {
+    /// Creates a new barcode.
+    /// Returns Result<RM4SCC, Error> indicating parse success.
+    pub fn new<T: AsRef<str>>(data: T) -> Result<RM4SCC> {
+        RM4SCC::parse(data.as_ref()).map(|d| RM4SCC(positions(d)))
+    }
+
+    /// Calculates the position of the check character, whose row and column are the sums of the
+    /// rows and columns (counting from 1) of the data, modulo 6.
+    fn checksum_position(&self) -> usize {
+        let (rows, columns) = self.0.iter().fold((0, 0), |(r, c), &p| (r + (p / 6) + 1, c + (p % 6) + 1));
+        let wrap = |n: usize| match n % 6 {
+            0 => 5,
+            n => n - 1,
+        };
+
+        (wrap(rows) * 6) + wrap(columns)
+    }
+
+    /// Encodes the barcode.
+    /// Returns a Vec<BarState> of the bars from left to right.
+    pub fn encode(&self) -> Vec<BarState> {
+        let mut enc = vec![BarState::Ascender];
+
+        for &p in &self.0 {
+            push_char(&mut enc, p);
+        }
+
+        push_char(&mut enc, self.checksum_position());
+        enc.push(BarState::Full);
+        enc
+    }
+}
+
+impl Parse for KIX {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> Range<u32> {
+        1..18
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        CHARS.chars().collect()
+    }
+}
+
+impl Parse for RM4SCC {
+    /// Returns the valid length of data acceptable in this type of barcode.
+    fn valid_len() -> Range<u32> {
+        1..50
+    }
+
+    /// Returns the set of valid characters allowed in this type of barcode.
+    fn valid_chars() -> Vec<char> {
+        CHARS.chars().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sym::postal::*;
+    use error::Error;
+
+    // 4-state bars aren't binary modules, so helpers::modules_to_string doesn't apply.
+    fn bars_to_string(v: Vec<BarState>) -> String {
+        v.into_iter().map(BarState::to_char).collect()
+    }
+
+    #[test]
+    fn new_kix() {
+        assert!(KIX::new("1231FZ13XHS").is_ok());
+        assert_eq!(KIX::new("").err().unwrap(), Error::Length);
+        assert_eq!(KIX::new("1231fz13xhs").err().unwrap(), Error::Character);
+        assert_eq!(KIX::new("1231 FZ").err().unwrap(), Error::Character);
+    }
+
+    #[test]
+    fn kix_encode() {
+        let kix = KIX::new("1231FZ13XHS").unwrap();
+
+        assert_eq!(bars_to_string(kix.encode()), "TDAFTDFADTAFTDAFDAADFFTTTDAFDTAFFATDDFATFTFT");
+    }
+
+    #[test]
+    fn new_rm4scc() {
+        assert!(RM4SCC::new("SN34RD1A").is_ok());
+        assert_eq!(RM4SCC::new("").err().unwrap(), Error::Length);
+        assert_eq!(RM4SCC::new("SN34 RD1A").err().unwrap(), Error::Character);
+    }
+
+    #[test]
+    fn rm4scc_encode() {
+        let rm4scc = RM4SCC::new("SN34RD1A").unwrap();
+
+        // Start bar, the data, the check character 'K' (ADDA) and the stop bar.
+        assert_eq!(bars_to_string(rm4scc.encode()), "AFTFTFDTADTAFDTFAFTADTFADTDAFDADAADDAF");
+        assert_eq!(rm4scc.checksum_position(), 20);
+    }
+}
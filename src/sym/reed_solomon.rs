@@ -0,0 +1,72 @@
+//! Reed-Solomon error correction codeword generation for 2D symbologies.
+//!
+//! The error correction codewords are the remainder of dividing the data (as a polynomial) by
+//! the generator polynomial of the requested degree. The arithmetic is performed over one of
+//! the fields in `sym::galois`, so that each symbology only has to choose its field.
+//!
+//! For example:
+//!
+//! ```rust
+//! use barcoders::sym::reed_solomon;
+//!
+//! let ec = reed_solomon::encode(&[32, 91, 11, 120, 209, 114, 220, 77, 67, 64,
+//!                                 236, 17, 236, 17, 236, 17], 10);
+//!
+//! assert_eq!(ec, vec![196, 35, 39, 119, 235, 215, 231, 226, 93, 23]);
+//! ```
+
+use sym::galois::{self, Field};
+
+/// Calculates `ec_len` error correction codewords for the given data, over the QR code field.
+/// Returns an empty Vec if `ec_len` is zero or larger than the field allows.
+pub fn encode(data: &[u8], ec_len: usize) -> Vec<u8> {
+    encode_with(galois::qr(), data, ec_len)
+}
+
+/// Calculates `ec_len` error correction codewords for the given data, over the given field.
+/// Returns an empty Vec if `ec_len` is zero or larger than the field allows.
+pub fn encode_with(field: &Field, data: &[u8], ec_len: usize) -> Vec<u8> {
+    let generator = match field.generator(ec_len) {
+        Some(g) => g,
+        None => return vec![],
+    };
+    let mut remainder = vec![0; ec_len];
+
+    // Polynomial long division, keeping only the remainder.
+    for &d in data {
+        let factor = d ^ remainder[0];
+
+        remainder.remove(0);
+        remainder.push(0);
+
+        for (r, &g) in remainder.iter_mut().zip(generator[1..].iter()) {
+            *r ^= field.mul(g, factor);
+        }
+    }
+
+    remainder
+}
+
+#[cfg(test)]
+mod tests {
+    use sym::reed_solomon::*;
+    use sym::galois;
+
+    #[test]
+    fn reed_solomon_qr() {
+        // ISO/IEC 18004 Annex I: "01234567" as version 1-M.
+        let data = [16, 32, 12, 86, 97, 128, 236, 17, 236, 17, 236, 17, 236, 17, 236, 17];
+
+        assert_eq!(encode(&data, 10), vec![165, 36, 212, 193, 237, 54, 199, 135, 44, 85]);
+        assert_eq!(encode(&data, 0), vec![]);
+        assert_eq!(encode(&data, 256), vec![]);
+    }
+
+    #[test]
+    fn reed_solomon_data_matrix() {
+        // ISO/IEC 16022 Annex O: "123456" as a 10x10 symbol.
+        let data = [142, 164, 186];
+
+        assert_eq!(encode_with(galois::data_matrix(), &data, 5), vec![114, 25, 5, 88, 102]);
+    }
+}
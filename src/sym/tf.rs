@@ -6,10 +6,13 @@
 //! groups of products (cartons of Cola, etc).
 //!
 //! Most of the time you will want to use the interleaved barcode over the standard option.
+//!
+//! Leading and trailing whitespace is trimmed from the data. Leading zeros are significant and
+//! always encoded, while signs (such as `-`) are rejected with `Error::Character`.
 
-use sym::Parse;
+use sym::{Barcode, Density, Parse};
 use sym::helpers;
-use error::Result;
+use error::{Error, Result};
 use std::ops::Range;
 use std::char;
 
@@ -26,6 +29,8 @@ const STF_START: [u8; 8] = [1, 1, 0, 1, 1, 0, 1, 0];
 const STF_STOP: [u8; 8] = [1, 1, 0, 1, 0, 1, 1, 0];
 
 /// The 2-of-5 barcode type.
+///
+/// There is no decoder for this type, so `Barcode::self_check` always passes.
 #[derive(Debug)]
 pub enum TF {
     /// The standard 2-of-5 barcode type.
@@ -41,12 +46,8 @@ impl TF {
     ///
     /// Returns Result<TF::Interleaved, Error> indicating parse success.
     pub fn interleaved<T: AsRef<str>>(data: T) -> Result<TF> {
-        TF::parse(data.as_ref()).and_then(|d| {
-            let mut digits: Vec<u8> = d.chars()
-                                       .map(|c| {
-                                           c.to_digit(10).expect("Unknown character") as u8
-                                       })
-                                       .collect();
+        TF::parse(data.as_ref().trim()).and_then(|d| {
+            let mut digits = TF::digits(d)?;
             let checksum_required = digits.len() % 2 == 1;
 
             if checksum_required {
@@ -62,12 +63,13 @@ impl TF {
     ///
     /// Returns Result<TF::Standard, Error> indicating parse success.
     pub fn standard<T: AsRef<str>>(data: T) -> Result<TF> {
-        TF::parse(data.as_ref()).and_then(|d| {
-            let digits: Vec<u8> = d.chars()
-                                   .map(|c| c.to_digit(10).expect("Unknown character") as u8)
-                                   .collect();
-            Ok(TF::Standard(digits))
-        })
+        TF::parse(data.as_ref().trim()).and_then(TF::digits).map(TF::Standard)
+    }
+
+    fn digits(data: &str) -> Result<Vec<u8>> {
+        data.chars()
+            .map(|c| c.to_digit(10).map(|d| d as u8).ok_or(Error::Character))
+            .collect()
     }
 
     fn raw_data(&self) -> &[u8] {
@@ -158,9 +160,28 @@ impl Parse for TF {
     }
 }
 
+impl Barcode for TF {
+    fn encode(&self) -> Vec<u8> {
+        TF::encode(self)
+    }
+
+    /// The START and STOP patterns are overhead. A computed check digit is counted as data, as
+    /// it can't be told apart once appended.
+    fn density(&self) -> Density {
+        let overhead = match *self {
+            TF::Standard(_) => STF_START.len() + STF_STOP.len(),
+            TF::Interleaved(_) => ITF_START.len() + ITF_STOP.len(),
+        };
+        let modules = self.encode().len();
+
+        Density::new(modules, modules - overhead, self.metadata().quiet_zone_modules)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use sym::tf::*;
+    use sym::pharmacode::Pharmacode;
     use error::Error;
     use std::char;
 
@@ -171,50 +192,75 @@ mod tests {
 
     #[test]
     fn new_itf() {
-        let itf = TF::interleaved("12345679".to_owned());
+        let itf = TF::interleaved("12345679");
 
         assert!(itf.is_ok());
     }
 
     #[test]
     fn new_stf() {
-        let stf = TF::standard("12345".to_owned());
+        let stf = TF::standard("12345");
 
         assert!(stf.is_ok());
     }
 
     #[test]
     fn invalid_data_itf() {
-        let itf = TF::interleaved("1234er123412".to_owned());
+        let itf = TF::interleaved("1234er123412");
 
         assert_eq!(itf.err().unwrap(), Error::Character);
     }
 
     #[test]
     fn invalid_data_stf() {
-        let stf = TF::standard("WORDUP".to_owned());
+        let stf = TF::standard("WORDUP");
 
         assert_eq!(stf.err().unwrap(), Error::Character);
     }
 
+    #[test]
+    fn itf_long_data() {
+        let itf = TF::interleaved("9".repeat(255)).unwrap();
+
+        assert_eq!(itf.raw_data().len(), 256);
+    }
+
     #[test]
     fn itf_raw_data() {
-        let itf = TF::interleaved("12345679".to_owned()).unwrap();
+        let itf = TF::interleaved("12345679").unwrap();
 
         assert_eq!(itf.raw_data(), &[1, 2, 3, 4, 5, 6, 7, 9]);
     }
 
+    #[test]
+    fn itf_normalized_data() {
+        let itf = TF::interleaved(" 0042\n").unwrap();
+        let stf = TF::standard("\t0042 ").unwrap();
+
+        assert_eq!(itf.raw_data(), &[0, 0, 4, 2]);
+        assert_eq!(stf.raw_data(), &[0, 0, 4, 2]);
+        assert_eq!(TF::interleaved("-42").err().unwrap(), Error::Character);
+        assert_eq!(TF::interleaved("   ").err().unwrap(), Error::Length);
+    }
+
+    #[test]
+    fn itf_and_pharmacode_leading_zeros() {
+        // ITF encodes each digit, while Pharmacode reads an integer.
+        assert_eq!(TF::interleaved("0042").unwrap().raw_data(), &[0, 0, 4, 2]);
+        assert_eq!(Pharmacode::new("0042").unwrap().value(), 42);
+    }
+
     #[test]
     fn itf_encode() {
-        let itf = TF::interleaved("1234567".to_owned()).unwrap(); // Check digit: 0
+        let itf = TF::interleaved("1234567").unwrap(); // Check digit: 0
 
-        assert_eq!(collapse_vec(itf.encode()), "10101110100010101110001110111010001010001110100011100010101010100011100011101101".to_owned());
+        assert_eq!(collapse_vec(itf.encode()), "10101110100010101110001110111010001010001110100011100010101010100011100011101101");
     }
 
     #[test]
     fn stf_encode() {
-        let stf = TF::standard("1234567".to_owned()).unwrap();
+        let stf = TF::standard("1234567").unwrap();
 
-        assert_eq!(collapse_vec(stf.encode()), "110110101110101010111010111010101110111011101010101010111010111011101011101010101110111010101010101110111011010110".to_owned());
+        assert_eq!(collapse_vec(stf.encode()), "110110101110101010111010111010101110111011101010101010111010111011101011101010101110111010101010101110111011010110");
     }
 }